@@ -1,7 +1,9 @@
-use tauri::command;
+use tauri::{command, State};
 use xcap::{Monitor, Window};
 use serde::{Serialize, Deserialize};
-use std::io::Cursor;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use image::DynamicImage;
 
@@ -17,8 +19,60 @@ pub struct CaptureSource {
     pub y: i32,
 }
 
+/// フレーム取得のスロットリング状態
+/// フロントのループが暴走しても、最小間隔より速い呼び出しには直前のフレームを返す
+pub struct FrameThrottle {
+    pub min_interval: Duration,
+    // source key -> (サイズ, キャプチャ時刻, Data URL) ソースごとに最新の1枚だけ保持する
+    pub last_frames: HashMap<String, ((Option<u32>, Option<u32>), Instant, String)>,
+}
+
+impl Default for FrameThrottle {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_millis(33), // 約30fps
+            last_frames: HashMap::new(),
+        }
+    }
+}
+
+pub struct FrameThrottleState(pub Arc<Mutex<FrameThrottle>>);
+
+/// キャプチャの最小間隔 (ms) を設定
+#[command]
+pub fn set_capture_min_interval(interval_ms: u64, state: State<'_, FrameThrottleState>) -> Result<(), String> {
+    let mut throttle = state.0.lock().map_err(|e| e.to_string())?;
+    throttle.min_interval = Duration::from_millis(interval_ms);
+    Ok(())
+}
+
+/// サムネイルの既定値
+const DEFAULT_THUMB_WIDTH: u32 = 300;
+const DEFAULT_THUMB_QUALITY: u8 = 75;
+
+/// キャプチャ画像を指定幅のJPEGサムネイル (Data URL) に変換
+fn encode_thumbnail(img: &image::RgbaImage, thumb_width: u32, quality: u8) -> Result<String, String> {
+    let aspect_ratio = img.width() as f32 / img.height().max(1) as f32;
+    let thumb_height = ((thumb_width as f32 / aspect_ratio) as u32).max(1);
+
+    let thumb = image::imageops::thumbnail(img, thumb_width, thumb_height);
+
+    // JPEGはRGBAをサポートしていないため、RGBに変換して書き込む
+    let mut buf = Vec::new();
+    let rgb_img = DynamicImage::ImageRgba8(thumb).to_rgb8();
+    let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality);
+    encoder.encode_image(&rgb_img).map_err(|e| e.to_string())?;
+
+    Ok(format!("data:image/jpeg;base64,{}", BASE64.encode(&buf)))
+}
+
+/// キャプチャ可能なモニター/ウィンドウ一覧をサムネイル付きで取得
+/// thumb_width: サムネイル幅 (既定 300px), quality: JPEG品質 1-100 (既定 75)
 #[command]
-pub async fn get_capture_sources() -> Result<Vec<CaptureSource>, String> {
+pub async fn get_capture_sources(thumb_width: Option<u32>, quality: Option<u8>) -> Result<Vec<CaptureSource>, String> {
+    let thumb_width = thumb_width.unwrap_or(DEFAULT_THUMB_WIDTH).clamp(16, 1920);
+    let quality = quality.unwrap_or(DEFAULT_THUMB_QUALITY).clamp(1, 100);
+
     let tasks = {
         let mut tasks = Vec::new();
 
@@ -33,24 +87,12 @@ pub async fn get_capture_sources() -> Result<Vec<CaptureSource>, String> {
                     .ok_or_else(|| format!("Monitor {} not found in task", monitor_id))?;
 
                 let img = monitor.capture_image().map_err(|e| e.to_string())?;
-                
-                let aspect_ratio = img.width() as f32 / img.height() as f32;
-                let thumb_width = 300;
-                let thumb_height = (thumb_width as f32 / aspect_ratio) as u32;
-                
-                let thumb = image::imageops::thumbnail(&img, thumb_width, thumb_height);
-                
-                let mut buf = Vec::new();
-                let mut cursor = Cursor::new(&mut buf);
-                // JPEGはRGBAをサポートしていないため、RGBに変換して書き込む
-                DynamicImage::ImageRgba8(thumb).to_rgb8().write_to(&mut cursor, image::ImageFormat::Jpeg).map_err(|e: image::ImageError| e.to_string())?;
-                
-                let b64 = BASE64.encode(&buf);
+                let thumbnail_base64 = encode_thumbnail(&img, thumb_width, quality)?;
                 
                 Ok::<CaptureSource, String>(CaptureSource {
                     id: monitor.id().map_err(|e| e.to_string())?.to_string(),
                     name: monitor.name().map_err(|e| e.to_string())?,
-                    thumbnail_base64: format!("data:image/jpeg;base64,{}", b64),
+                    thumbnail_base64,
                     is_monitor: true,
                     width: monitor.width().map_err(|e| e.to_string())?,
                     height: monitor.height().map_err(|e| e.to_string())?,
@@ -82,26 +124,12 @@ pub async fn get_capture_sources() -> Result<Vec<CaptureSource>, String> {
                 }
 
                 let img = window.capture_image().map_err(|e| e.to_string())?;
-                
-                let aspect_ratio = img.width() as f32 / img.height() as f32;
-                let thumb_width = 300;
-                let thumb_height = (thumb_width as f32 / aspect_ratio) as u32;
-                
-                let thumb = image::imageops::thumbnail(&img, thumb_width, thumb_height);
-                
-                let mut buf = Vec::new();
-                let mut cursor = Cursor::new(&mut buf);
-                // JPEGはRGBAをサポートしていないため、RGBに変換して書き込む
-                DynamicImage::ImageRgba8(thumb).to_rgb8()
-                    .write_to(&mut cursor, image::ImageFormat::Jpeg)
-                    .map_err(|e: image::ImageError| e.to_string())?;
-                
-                let b64 = BASE64.encode(&buf);
+                let thumbnail_base64 = encode_thumbnail(&img, thumb_width, quality)?;
                 
                 Ok::<CaptureSource, String>(CaptureSource {
                     id: window.id().map_err(|e| e.to_string())?.to_string(),
                     name: title,
-                    thumbnail_base64: format!("data:image/jpeg;base64,{}", b64),
+                    thumbnail_base64,
                     is_monitor: false,
                     width: window.width().map_err(|e| e.to_string())?,
                     height: window.height().map_err(|e| e.to_string())?,
@@ -130,10 +158,51 @@ pub async fn get_capture_sources() -> Result<Vec<CaptureSource>, String> {
     Ok(sources)
 }
 
+/// キャプチャ画像を (必要なら縮小して) JPEG Data URL に変換
+fn encode_frame(img: image::RgbaImage, width: Option<u32>, height: Option<u32>) -> Result<String, String> {
+    // リサイズ（必要な場合）
+    let img_to_encode = if let (Some(w), Some(h)) = (width, height) {
+        if img.width() > w || img.height() > h {
+            image::imageops::thumbnail(&img, w, h)
+        } else {
+            img
+        }
+    } else {
+        img
+    };
+
+    // JPEG エンコード（RGBに変換が必要）
+    let mut buf = Vec::new();
+    let rgb_img = DynamicImage::ImageRgba8(img_to_encode).to_rgb8();
+    let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, 70);
+    encoder.encode_image(&rgb_img).map_err(|e| e.to_string())?;
+
+    // Base64 Data URL として返す
+    Ok(format!("data:image/jpeg;base64,{}", BASE64.encode(&buf)))
+}
+
 /// シンプルなフレーム取得コマンド - JPEG + Base64で安定動作
 #[command]
-pub async fn get_source_frame(id: String, is_monitor: bool, width: Option<u32>, height: Option<u32>) -> Result<String, String> {
-    tokio::task::spawn_blocking(move || {
+pub async fn get_source_frame(
+    id: String,
+    is_monitor: bool,
+    width: Option<u32>,
+    height: Option<u32>,
+    throttle_state: State<'_, FrameThrottleState>,
+) -> Result<String, String> {
+    // 同じソース・サイズへの呼び出しが最小間隔内ならキャッシュを返す
+    let key = format!("{}:{}", if is_monitor { "monitor" } else { "window" }, id);
+    let size = (width, height);
+    {
+        let throttle = throttle_state.0.lock().map_err(|e| e.to_string())?;
+        if let Some((cached_size, captured_at, frame)) = throttle.last_frames.get(&key) {
+            if *cached_size == size && captured_at.elapsed() < throttle.min_interval {
+                return Ok(frame.clone());
+            }
+        }
+    }
+
+    let frame = tokio::task::spawn_blocking(move || {
         // キャプチャ対象を取得
         let img = if is_monitor {
             let monitors = Monitor::all().map_err(|e| e.to_string())?;
@@ -149,24 +218,42 @@ pub async fn get_source_frame(id: String, is_monitor: bool, width: Option<u32>,
             window.capture_image().map_err(|e| e.to_string())?
         };
 
-        // リサイズ（必要な場合）
-        let img_to_encode = if let (Some(w), Some(h)) = (width, height) {
-            if img.width() > w || img.height() > h {
-                image::imageops::thumbnail(&img, w, h)
-            } else {
-                img
-            }
-        } else {
-            img
-        };
+        encode_frame(img, width, height)
+    }).await.map_err(|e| e.to_string())??;
+
+    if let Ok(mut throttle) = throttle_state.0.lock() {
+        // 最小間隔を過ぎたフレームは二度と返さないので、使われなくなったソースの分も含めて捨てる
+        let min_interval = throttle.min_interval;
+        throttle.last_frames.retain(|_, (_, captured_at, _)| captured_at.elapsed() < min_interval);
+        throttle.last_frames.insert(key, (size, Instant::now(), frame.clone()));
+    }
+
+    Ok(frame)
+}
+
+/// タイトルに指定文字列を含む最初のウィンドウ (最小化されていないもの) をキャプチャ
+/// 大文字小文字は区別しない。自動化・スクリプト用
+#[command]
+pub async fn get_window_frame_by_title(
+    title_substring: String,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<String, String> {
+    if title_substring.is_empty() {
+        return Err("title_substring must not be empty".to_string());
+    }
 
-        // JPEG エンコード（RGBに変換が必要）
-        let mut buf = Vec::new();
-        let rgb_img = DynamicImage::ImageRgba8(img_to_encode).to_rgb8();
-        let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, 70);
-        encoder.encode_image(&rgb_img).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let needle = title_substring.to_lowercase();
+        let windows = Window::all().map_err(|e| e.to_string())?;
+        let window = windows.into_iter()
+            .find(|w| {
+                !w.is_minimized().unwrap_or(true)
+                    && w.title().map(|t| t.to_lowercase().contains(&needle)).unwrap_or(false)
+            })
+            .ok_or_else(|| format!("No visible window with title containing \"{}\"", title_substring))?;
 
-        // Base64 Data URL として返す
-        Ok(format!("data:image/jpeg;base64,{}", BASE64.encode(&buf)))
+        let img = window.capture_image().map_err(|e| e.to_string())?;
+        encode_frame(img, width, height)
     }).await.map_err(|e| e.to_string())?
 }
@@ -2,52 +2,482 @@ use tauri::{AppHandle, Emitter, Manager};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use url::Url;
 use serde_json::Value;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use futures_util::{StreamExt, SinkExt};
 
 const GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
 
 use tokio::sync::mpsc::UnboundedSender;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::State;
+use crate::services::guild_state::GuildMemberStore;
 
 pub struct GatewaySender(pub Arc<Mutex<Option<UnboundedSender<Message>>>>);
 pub struct SessionState(pub Arc<Mutex<Option<String>>>);
 
+/// 再接続ループの世代。start_gateway / shutdown_gateway のたびに進め、
+/// 自分の世代でなくなったループは再接続せずに終了する (二重ループ防止)
+#[derive(Default)]
+pub struct GatewayShutdown(pub Arc<AtomicU64>);
+
+/// 自分が「入っている」Discordボイスチャンネルとミュート状態
+#[derive(Default, Clone)]
+pub struct CurrentVoiceState {
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub self_mute: bool,
+    pub self_deaf: bool,
+}
+
+pub struct VoiceChannelState(pub Arc<Mutex<CurrentVoiceState>>);
+
+/// フロントエンドで表示中のチャンネル (guild_id, channel_id)。再接続時の取りこぼし補完に使う
+#[derive(Default)]
+pub struct ActiveChannelState(pub Arc<Mutex<Option<(Option<String>, String)>>>);
+
+/// AFK監視の確認間隔
+const AFK_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 無操作時の自動退席 (idle) 状態
+pub struct AfkWatch {
+    pub timeout: Option<Duration>, // None の場合は無効 (オプトイン)
+    pub last_active: Instant,
+    pub user_status: String,       // ユーザーが選択したステータス (復帰時に戻す)
+    pub is_idle: bool,             // 自動で idle に切り替え済み
+}
+
+impl Default for AfkWatch {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            last_active: Instant::now(),
+            user_status: "online".to_string(),
+            is_idle: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AfkState(pub Arc<Mutex<AfkWatch>>);
+
+/// 直近に send_message で送信したメッセージIDを保持する件数
+const RECENT_SENT_CAPACITY: usize = 64;
+
+/// 直近に自分が送信したメッセージID (Gatewayのエコー判定用リングバッファ)
+#[derive(Default)]
+pub struct RecentSentMessages(pub Arc<Mutex<VecDeque<String>>>);
+
+impl RecentSentMessages {
+    pub fn record(&self, id: String) {
+        if let Ok(mut ids) = self.0.lock() {
+            if ids.len() >= RECENT_SENT_CAPACITY {
+                ids.pop_front();
+            }
+            ids.push_back(id);
+        }
+    }
+
+    /// 自分が送信したIDならバッファから取り除いて true を返す
+    pub fn take(&self, id: &str) -> bool {
+        let Ok(mut ids) = self.0.lock() else { return false };
+        match ids.iter().position(|sent| sent == id) {
+            Some(pos) => {
+                ids.remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 入力中表示の有効期間 (Discordは入力停止イベントを送らないので、期限切れで消す)
+const TYPING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// チャンネルごとの入力中ユーザー (channel_id -> { user_id -> TYPING_START 受信時刻 })
+#[derive(Default)]
+pub struct TypingState(pub Arc<Mutex<HashMap<String, HashMap<String, Instant>>>>);
+
+impl TypingState {
+    /// 期限切れを除いた入力中ユーザーID (受信順)
+    fn typing_users(typers: &HashMap<String, Instant>) -> Vec<String> {
+        let mut users: Vec<(&String, &Instant)> = typers.iter().collect();
+        users.sort_by_key(|(_, started)| **started);
+        users.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// 入力開始を記録し、現在の入力中ユーザーを返す
+    fn start(&self, channel_id: &str, user_id: &str) -> Vec<String> {
+        let Ok(mut channels) = self.0.lock() else { return vec![] };
+        let typers = channels.entry(channel_id.to_string()).or_default();
+        typers.insert(user_id.to_string(), Instant::now());
+        Self::typing_users(typers)
+    }
+
+    /// ユーザーを入力中から外す (メッセージ送信時)。変化があれば現在の入力中ユーザーを返す
+    fn stop(&self, channel_id: &str, user_id: &str) -> Option<Vec<String>> {
+        let mut channels = self.0.lock().ok()?;
+        let typers = channels.get_mut(channel_id)?;
+        typers.remove(user_id)?;
+        let users = Self::typing_users(typers);
+        if typers.is_empty() {
+            channels.remove(channel_id);
+        }
+        Some(users)
+    }
+
+    /// 期限切れのユーザーを外す。変化があれば現在の入力中ユーザーを返す
+    fn expire(&self, channel_id: &str) -> Option<Vec<String>> {
+        let mut channels = self.0.lock().ok()?;
+        let typers = channels.get_mut(channel_id)?;
+        let before = typers.len();
+        typers.retain(|_, started| started.elapsed() < TYPING_TIMEOUT);
+        if typers.len() == before {
+            return None;
+        }
+        let users = Self::typing_users(typers);
+        if typers.is_empty() {
+            channels.remove(channel_id);
+        }
+        Some(users)
+    }
+}
+
+/// チャンネルの入力中ユーザー一覧を通知
+fn emit_typing_update(app: &AppHandle, channel_id: &str, user_ids: Vec<String>) {
+    let _ = app.emit("typing_update", serde_json::json!({
+        "channel_id": channel_id,
+        "user_ids": user_ids,
+    }));
+}
+
+/// OP 4: Voice State Update ペイロードを送信
+fn send_voice_state_update(sender: &UnboundedSender<Message>, voice: &CurrentVoiceState) -> Result<(), String> {
+    let payload = serde_json::json!({
+        "op": 4,
+        "d": {
+            "guild_id": voice.guild_id,
+            "channel_id": voice.channel_id,
+            "self_mute": voice.self_mute,
+            "self_deaf": voice.self_deaf
+        }
+    });
+    sender.send(Message::Text(payload.to_string())).map_err(|e| e.to_string())
+}
+
+/// 再接続バックオフ: 5秒から倍々で最大60秒
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(5);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+/// この時間以上接続が続いた場合はバックオフをリセット
+const RECONNECT_RESET_AFTER: Duration = Duration::from_secs(60);
+
+/// 全クライアントが同時に再接続しないよう ±20% のジッターを加える
+/// (rand クレートは使わず、現在時刻のナノ秒部分を乱数源にする)
+fn with_jitter(delay: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let factor = 0.8 + (nanos % 1_000) as f64 / 1_000.0 * 0.4;
+    delay.mul_f64(factor)
+}
+
 #[tauri::command]
-pub async fn start_gateway(app: AppHandle, token: String, state: State<'_, GatewaySender>, session_state: State<'_, SessionState>) -> Result<(), String> {
+pub async fn start_gateway(
+    app: AppHandle,
+    token: String,
+    state: State<'_, GatewaySender>,
+    session_state: State<'_, SessionState>,
+    shutdown: State<'_, GatewayShutdown>
+) -> Result<(), String> {
     let state_clone = state.0.clone();
     let session_clone = session_state.0.clone();
+    let loop_epoch = shutdown.0.clone();
+    let epoch = loop_epoch.fetch_add(1, Ordering::SeqCst) + 1;
     tokio::spawn(async move {
+        let mut delay = RECONNECT_BASE_DELAY;
         loop {
             println!("Connecting to Gateway...");
-            match connect_to_gateway(&app, &token, state_clone.clone(), session_clone.clone()).await {
-                Ok(_) => println!("Gateway connection closed, reconnecting..."),
-                Err(e) => {
-                    eprintln!("Gateway error: {}", e);
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+            let started = Instant::now();
+            let result = connect_to_gateway(&app, &token, state_clone.clone(), session_clone.clone()).await;
+            if loop_epoch.load(Ordering::SeqCst) != epoch {
+                println!("Gateway stopped");
+                break;
+            }
+
+            // しばらく接続が続いていたら正常接続だったとみなしてバックオフをリセット
+            if started.elapsed() >= RECONNECT_RESET_AFTER {
+                delay = RECONNECT_BASE_DELAY;
+            }
+            match result {
+                Ok(_) => println!("Gateway connection closed"),
+                Err(e) => eprintln!("Gateway error: {}", e),
+            }
+
+            let wait = with_jitter(delay);
+            println!("Reconnecting to Gateway in {:.1}s", wait.as_secs_f64());
+            tokio::time::sleep(wait).await;
+            delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+            // 待機中に停止・再ログインされていたら再接続しない
+            if loop_epoch.load(Ordering::SeqCst) != epoch {
+                println!("Gateway stopped");
+                break;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Gatewayを停止する (アプリ終了時・トークン失効時)
+/// ボイスチャンネルに入っていれば退出を送ってから接続を閉じ、再接続ループを止める
+pub fn shutdown_gateway(app: &AppHandle) {
+    if let Some(shutdown) = app.try_state::<GatewayShutdown>() {
+        shutdown.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let Some(sender_state) = app.try_state::<GatewaySender>() else { return };
+    let Ok(mut guard) = sender_state.0.lock() else { return };
+    if let Some(sender) = guard.take() {
+        if let Some(voice_state) = app.try_state::<VoiceChannelState>() {
+            if let Ok(mut voice) = voice_state.0.lock() {
+                if voice.channel_id.is_some() {
+                    voice.guild_id = None;
+                    voice.channel_id = None;
+                    let _ = send_voice_state_update(&sender, &voice);
+                }
+            }
+        }
+        let _ = sender.send(Message::Close(None));
+    }
+}
+
+/// OP 3 Presence Update を送信 (afk = true の場合は自動退席として送る)
+fn send_presence_update(sender: &UnboundedSender<Message>, status: &str, afk: bool) -> Result<(), String> {
+    // 自動退席の場合は退席した時刻 (Unix ミリ秒) を since に入れる
+    let since = afk.then(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    });
+    let payload = serde_json::json!({
+        "op": 3,
+        "d": {
+            "since": since,
+            "activities": [],
+            "status": status,
+            "afk": afk
+        }
+    });
+
+    sender.send(Message::Text(payload.to_string())).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_status(status: String, state: State<'_, GatewaySender>, afk_state: State<'_, AfkState>) -> Result<(), String> {
+    {
+        let sender_guard = state.0.lock().unwrap();
+        let sender = sender_guard.as_ref().ok_or("Gateway not connected")?;
+        send_presence_update(sender, &status, false)?;
+    }
+
+    // 手動で選んだステータスを復帰先として記録
+    // (ロック順序を AfkState → GatewaySender に揃えるため、送信後にロックする)
+    if let Ok(mut afk) = afk_state.0.lock() {
+        afk.user_status = status;
+        afk.is_idle = false;
+        afk.last_active = Instant::now();
+    }
+    Ok(())
+}
+
+/// 表示中のチャンネルを記録 (channel_id = None で未選択)
+#[tauri::command]
+pub fn set_active_channel(guild_id: Option<String>, channel_id: Option<String>, state: State<'_, ActiveChannelState>) -> Result<(), String> {
+    let mut active = state.0.lock().map_err(|e| e.to_string())?;
+    *active = channel_id.map(|c| (guild_id, c));
+    Ok(())
+}
+
+/// 表示中のチャンネルかどうか
+fn is_active_channel(app: &AppHandle, channel_id: &str) -> bool {
+    app.try_state::<ActiveChannelState>()
+        .and_then(|state| state.0.lock().ok().map(|active| {
+            active.as_ref().is_some_and(|(_, c)| c == channel_id)
+        }))
+        .unwrap_or(false)
+}
+
+/// 再接続中に取りこぼしたメッセージを REST (?after=キャッシュ済み最新ID) で取得し、
+/// 通常の message_create として古い順に流す (表示中のチャンネルのみ)
+/// 起点のIDは READY 処理中に同期的に読む (タスク内で読むと、後続の MESSAGE_CREATE が先に
+/// キャッシュへ入って起点が進み、その間のメッセージを取りこぼすため)
+fn replay_missed_messages(app: &AppHandle) {
+    let Some((guild_id, channel_id)) = app.try_state::<ActiveChannelState>()
+        .and_then(|state| state.0.lock().ok().and_then(|active| active.clone())) else {
+        return;
+    };
+    let Some(db_state) = app.try_state::<crate::store::DatabaseState>() else {
+        return;
+    };
+
+    // キャッシュが無いチャンネルは通常の取得に任せる
+    let newest = match db_state.conn.lock() {
+        Ok(conn) => crate::store::newest_cached_message_id(&conn, &channel_id).ok().flatten(),
+        Err(_) => None,
+    };
+    let Some(after_id) = newest else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(client) = app.try_state::<crate::services::state::DiscordState>()
+            .and_then(|state| state.client.lock().ok().and_then(|c| c.as_ref().cloned())) else {
+            return;
+        };
+        let Some(db_state) = app.try_state::<crate::store::DatabaseState>() else {
+            return;
+        };
+
+        let guild_id = guild_id.unwrap_or_default();
+        match crate::bridge::social::sync_messages_after(&client, &db_state.conn, guild_id, channel_id.clone(), after_id).await {
+            Ok(mut missed) => {
+                println!("[Gateway] Replaying {} missed message(s) for channel {}", missed.len(), channel_id);
+                missed.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+                for m in missed {
+                    let mut payload = serde_json::to_value(&m).unwrap_or(Value::Null);
+                    if let Some(obj) = payload.as_object_mut() {
+                        obj.insert("self_echo".to_string(), Value::Bool(false));
+                    }
+                    let _ = app.emit("message_create", payload);
                 }
             }
+            Err(e) => println!("[Gateway] Failed to fetch missed messages: {}", e),
         }
     });
+}
+
+/// 自動退席までの無操作時間を設定 (None または 0 で無効)
+#[tauri::command]
+pub fn set_afk_timeout(minutes: Option<u32>, afk_state: State<'_, AfkState>) -> Result<(), String> {
+    let mut afk = afk_state.0.lock().map_err(|e| e.to_string())?;
+    afk.timeout = minutes.filter(|m| *m > 0).map(|m| Duration::from_secs(m as u64 * 60));
+    afk.last_active = Instant::now();
     Ok(())
 }
 
+/// フロントエンドからの操作通知 (自動退席中ならユーザーのステータスに戻す)
+#[tauri::command]
+pub fn user_active(state: State<'_, GatewaySender>, afk_state: State<'_, AfkState>) -> Result<(), String> {
+    let mut afk = afk_state.0.lock().map_err(|e| e.to_string())?;
+    afk.last_active = Instant::now();
+
+    if afk.is_idle {
+        let sender_guard = state.0.lock().map_err(|e| e.to_string())?;
+        if let Some(sender) = &*sender_guard {
+            send_presence_update(sender, &afk.user_status, false)?;
+        }
+        afk.is_idle = false;
+    }
+    Ok(())
+}
+
+/// 無操作時間を監視し、タイムアウトしたら idle に切り替えるバックグラウンドタスクを起動
+/// ユーザーが online を選んでいる場合のみ切り替える (dnd / invisible はそのまま)
+pub fn init_afk_watch(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(AFK_CHECK_INTERVAL).await;
+
+            let (Some(afk_state), Some(sender_state)) = (app.try_state::<AfkState>(), app.try_state::<GatewaySender>()) else {
+                continue;
+            };
+            let Ok(mut afk) = afk_state.0.lock() else { continue };
+            let Some(timeout) = afk.timeout else { continue };
+            if afk.is_idle || afk.user_status != "online" || afk.last_active.elapsed() < timeout {
+                continue;
+            }
+
+            let Ok(sender_guard) = sender_state.0.lock() else { continue };
+            if let Some(sender) = &*sender_guard {
+                if send_presence_update(sender, "idle", true).is_ok() {
+                    println!("[Gateway] No activity for {:?}, switching to idle", timeout);
+                    afk.is_idle = true;
+                }
+            }
+        }
+    });
+}
+
+/// ボイスチャンネルへの参加/退出 (channel_id = None で退出)
 #[tauri::command]
-pub async fn update_status(status: String, state: State<'_, GatewaySender>) -> Result<(), String> {
+pub async fn set_voice_channel(
+    guild_id: Option<String>,
+    channel_id: Option<String>,
+    state: State<'_, GatewaySender>,
+    voice_state: State<'_, VoiceChannelState>
+) -> Result<(), String> {
+    let sender_guard = state.0.lock().map_err(|e| e.to_string())?;
+    let sender = sender_guard.as_ref().ok_or("Gateway not connected")?;
+
+    let mut voice = voice_state.0.lock().map_err(|e| e.to_string())?;
+    voice.guild_id = guild_id;
+    voice.channel_id = channel_id;
+    send_voice_state_update(sender, &voice)
+}
+
+/// P2D側のミュート/スピーカーミュート状態をDiscordにも反映
+#[tauri::command]
+pub async fn set_self_mute_deaf(
+    self_mute: bool,
+    self_deaf: bool,
+    state: State<'_, GatewaySender>,
+    voice_state: State<'_, VoiceChannelState>
+) -> Result<(), String> {
+    // ロック順は set_voice_channel / shutdown_gateway と同じく GatewaySender → VoiceChannelState
+    let sender_guard = state.0.lock().map_err(|e| e.to_string())?;
+    let mut voice = voice_state.0.lock().map_err(|e| e.to_string())?;
+    voice.self_mute = self_mute;
+    voice.self_deaf = self_deaf;
+
+    // ボイスチャンネルに入っていない場合は状態だけ保持（次回参加時に反映）
+    if voice.channel_id.is_none() {
+        return Ok(());
+    }
+
+    let sender = sender_guard.as_ref().ok_or("Gateway not connected")?;
+    send_voice_state_update(sender, &voice)
+}
+
+/// OP 8: Request Guild Members - 大規模ギルドの全メンバーを取得
+/// レスポンスは GUILD_MEMBERS_CHUNK で分割して届く
+#[tauri::command]
+pub async fn request_guild_members(
+    guild_id: String,
+    query: Option<String>,
+    limit: Option<u32>,
+    state: State<'_, GatewaySender>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<(), String> {
+    // 前回の途中状態をリセット
+    if let Ok(mut store) = guild_state.lock() {
+        store.member_chunks_received.remove(&guild_id);
+    }
+
     let sender_guard = state.0.lock().unwrap();
     if let Some(sender) = &*sender_guard {
-        // Construct Presence Update payload
+        println!("[Gateway] Sending OP 8 Request Guild Members for guild: {}", guild_id);
+
         let payload = serde_json::json!({
-            "op": 3,
+            "op": 8,
             "d": {
-                "since": null,
-                "activities": [],
-                "status": status,
-                "afk": false
+                "guild_id": guild_id,
+                "query": query.unwrap_or_default(),
+                "limit": limit.unwrap_or(0), // 0 = 上限なし
+                "presences": true
             }
         });
-        
+
         sender.send(Message::Text(payload.to_string())).map_err(|e| e.to_string())?;
         Ok(())
     } else {
@@ -55,6 +485,24 @@ pub async fn update_status(status: String, state: State<'_, GatewaySender>) -> R
     }
 }
 
+/// OP 14: Lazy Request ペイロードを送信
+fn send_lazy_request(sender: &UnboundedSender<Message>, guild_id: &str, channel_id: &str, ranges: &[[u32; 2]]) -> Result<(), String> {
+    let payload = serde_json::json!({
+        "op": 14,
+        "d": {
+            "guild_id": guild_id,
+            "typing": true,
+            "threads": true,
+            "activities": true,
+            "members": [],
+            "channels": {
+                (channel_id): ranges
+            }
+        }
+    });
+    sender.send(Message::Text(payload.to_string())).map_err(|e| e.to_string())
+}
+
 /// OP 14: Lazy Request - メンバーリストを購読
 #[tauri::command]
 pub async fn subscribe_member_list(
@@ -65,29 +513,38 @@ pub async fn subscribe_member_list(
     let sender_guard = state.0.lock().unwrap();
     if let Some(sender) = &*sender_guard {
         println!("[Gateway] Sending OP 14 Lazy Request for guild: {}, channel: {}", guild_id, channel_id);
-        
-        // OP 14: Lazy Request payload
-        let payload = serde_json::json!({
-            "op": 14,
-            "d": {
-                "guild_id": guild_id,
-                "typing": true,
-                "threads": true,
-                "activities": true,
-                "members": [],
-                "channels": {
-                    (channel_id): [[0, 99]]  // 最初の100人を要求
-                }
-            }
-        });
-        
-        sender.send(Message::Text(payload.to_string())).map_err(|e| e.to_string())?;
-        Ok(())
+        // 最初の100人を要求
+        send_lazy_request(sender, &guild_id, &channel_id, &[[0, 99]])
     } else {
         Err("Gateway not connected".to_string())
     }
 }
 
+/// OP 14 で一度に購読できる範囲数の上限
+const MAX_MEMBER_LIST_RANGES: usize = 3;
+
+/// OP 14: 購読範囲を更新 (メンバーサイドバーのスクロールに合わせて追加読み込み)
+/// 各範囲は [n*100, n*100+99] の100人単位である必要がある
+#[tauri::command]
+pub async fn update_member_list_range(
+    guild_id: String,
+    channel_id: String,
+    ranges: Vec<[u32; 2]>,
+    state: State<'_, GatewaySender>
+) -> Result<(), String> {
+    if ranges.is_empty() || ranges.len() > MAX_MEMBER_LIST_RANGES {
+        return Err(format!("ranges must contain 1 to {} entries", MAX_MEMBER_LIST_RANGES));
+    }
+    if let Some([start, end]) = ranges.iter().find(|[start, end]| start % 100 != 0 || start.checked_add(99) != Some(*end)) {
+        return Err(format!("Invalid range [{}, {}]: ranges must be 100-member windows starting at a multiple of 100", start, end));
+    }
+
+    let sender_guard = state.0.lock().unwrap();
+    let sender = sender_guard.as_ref().ok_or("Gateway not connected")?;
+    println!("[Gateway] Updating OP 14 ranges for guild: {}, channel: {}, ranges: {:?}", guild_id, channel_id, ranges);
+    send_lazy_request(sender, &guild_id, &channel_id, &ranges)
+}
+
 async fn connect_to_gateway(
     app: &AppHandle,
     token: &str,
@@ -137,16 +594,17 @@ async fn connect_to_gateway(
                         println!("Hello received. Heartbeat interval: {}", heartbeat_interval);
                         
                         // Send Identify
+                        let identify_config = crate::services::config::identify_config();
                         let identify = serde_json::json!({
                             "op": 2,
                             "d": {
                                 "token": token_clone,
                                 "properties": {
-                                    "os": "windows",
-                                    "browser": "p2d",
-                                    "device": "p2d"
+                                    "os": identify_config.os,
+                                    "browser": identify_config.browser,
+                                    "device": identify_config.device
                                 },
-                                "capabilities": 16381,
+                                "capabilities": identify_config.capabilities,
                                 "compress": false,
                                 "presence": {
                                     "status": "online",
@@ -178,22 +636,47 @@ async fn connect_to_gateway(
                         if t == "READY" {
                             if let Some(session_id) = v["d"]["session_id"].as_str() {
                                 println!("Received READY event, session_id: {}", session_id);
-                                if let Ok(mut lock) = session_state.lock() {
-                                    *lock = Some(session_id.to_string());
+                                let reconnected = match session_state.lock() {
+                                    Ok(mut lock) => lock.replace(session_id.to_string()).is_some(),
+                                    Err(_) => false,
+                                };
+                                // 再接続の場合は切断中に取りこぼしたメッセージを補完
+                                if reconnected {
+                                    replay_missed_messages(app);
                                 }
                             }
+                            handle_ready_read_state(app, &v["d"]["read_state"]);
+                            handle_ready_guild_settings(app, &v["d"]["user_guild_settings"]);
+                            handle_ready_private_channels(app, &v["d"]);
                         }
 
                         if t == "MESSAGE_CREATE" {
                             match serde_json::from_value::<crate::services::models::SimpleMessage>(map_message(&v["d"])) {
                                 Ok(m) => {
-                                    // DBに保存
+                                    // DBに保存 (ミュート中のチャンネルも保存はする)
+                                    let mut muted = false;
                                     if let Some(db_state) = app.try_state::<crate::store::DatabaseState>() {
                                         if let Ok(conn) = db_state.conn.lock() {
                                             let _ = crate::store::save_message(&conn, &m);
+                                            muted = crate::store::channel_settings::is_channel_muted(&conn, &m.channel_id);
                                         }
                                     }
-                                    let _ = app.emit("message_create", m);
+                                    // 発言したユーザーは入力中ではなくなる
+                                    if let Some(user_ids) = app.try_state::<TypingState>().and_then(|t| t.stop(&m.channel_id, &m.author_id)) {
+                                        emit_typing_update(app, &m.channel_id, user_ids);
+                                    }
+                                    // send_message で送信済みのものは self_echo を付けて流す (フロントの二重表示防止)
+                                    let self_echo = app.try_state::<RecentSentMessages>()
+                                        .map(|recent| recent.take(&m.id))
+                                        .unwrap_or(false);
+                                    // ミュート中のチャンネルは、表示中でなければフロントに通知しない
+                                    if !muted || is_active_channel(app, &m.channel_id) {
+                                        let mut payload = serde_json::to_value(&m).unwrap_or(Value::Null);
+                                        if let Some(obj) = payload.as_object_mut() {
+                                            obj.insert("self_echo".to_string(), Value::Bool(self_echo));
+                                        }
+                                        let _ = app.emit("message_create", payload);
+                                    }
                                 },
                                 Err(e) => {
                                     println!("[Gateway] Failed to parse message: {:?}", e);
@@ -229,6 +712,53 @@ async fn connect_to_gateway(
                         else if t == "GUILD_MEMBER_LIST_UPDATE" {
                             handle_member_list_update(app, &v["d"]);
                         }
+                        // GUILD_MEMBERS_CHUNK: OP 8 レスポンス
+                        else if t == "GUILD_MEMBERS_CHUNK" {
+                            handle_guild_members_chunk(app, &v["d"]);
+                        }
+                        // GUILD_CREATE: 接続時のギルド初期データ
+                        else if t == "GUILD_CREATE" {
+                            handle_guild_create(app, &v["d"]);
+                        }
+                        // APPLICATION_COMMAND_AUTOCOMPLETE_RESPONSE: スラッシュコマンドの候補
+                        else if t == "APPLICATION_COMMAND_AUTOCOMPLETE_RESPONSE" {
+                            let payload = serde_json::json!({
+                                "nonce": v["d"]["nonce"],
+                                "choices": v["d"]["choices"],
+                            });
+                            let _ = app.emit("autocomplete_response", payload);
+                        }
+                        // INTERACTION_MODAL_CREATE: BOTがモーダルを表示 (id は send_modal_submit で返す)
+                        else if t == "INTERACTION_MODAL_CREATE" {
+                            let payload = serde_json::json!({
+                                "id": v["d"]["id"],
+                                "nonce": v["d"]["nonce"],
+                                "channel_id": v["d"]["channel_id"],
+                                "application_id": v["d"]["application"]["id"],
+                                "custom_id": v["d"]["custom_id"],
+                                "title": v["d"]["title"],
+                                "components": v["d"]["components"],
+                            });
+                            let _ = app.emit("interaction_modal_create", payload);
+                        }
+                        // CHANNEL_* / THREAD_*: チャンネル・スレッドの追加・変更・削除
+                        else if t == "CHANNEL_CREATE" || t == "CHANNEL_UPDATE" || t == "THREAD_CREATE" || t == "THREAD_UPDATE" {
+                            handle_channel_upsert(app, &v["d"]);
+                        }
+                        else if t == "CHANNEL_DELETE" || t == "THREAD_DELETE" {
+                            handle_channel_delete(app, &v["d"]);
+                        }
+                        else if t == "THREAD_LIST_SYNC" {
+                            handle_thread_list_sync(app, &v["d"]);
+                        }
+                        // GUILD_ROLE_CREATE / GUILD_ROLE_UPDATE: ロールの追加・変更 (色・名前など)
+                        else if t == "GUILD_ROLE_CREATE" || t == "GUILD_ROLE_UPDATE" {
+                            handle_guild_role_update(app, &v["d"]);
+                        }
+                        // GUILD_ROLE_DELETE: ロールの削除
+                        else if t == "GUILD_ROLE_DELETE" {
+                            handle_guild_role_delete(app, &v["d"]);
+                        }
                     },
                     _ => {}
                 }
@@ -249,6 +779,7 @@ fn map_message(d: &Value) -> Value {
     let author_id = d["author"]["id"].as_str().unwrap_or("").to_string();
     let embeds = d.get("embeds").unwrap_or(&serde_json::json!([])).clone();
     let attachments = d.get("attachments").unwrap_or(&serde_json::json!([])).clone();
+    let components = d.get("components").unwrap_or(&serde_json::json!([])).clone();
     let guild_id = d["guild_id"].as_str().unwrap_or("").to_string();
     
     // Referenced Message Mapping (Simplified to avoid recursion complexity in single pass)
@@ -270,7 +801,9 @@ fn map_message(d: &Value) -> Value {
             "attachments": rm_attachments,
             "referenced_message": null,
             "message_snapshots": [],
-            "kind": "Default"
+            "kind": "Default",
+            "reactions": map_reactions(rm),
+            "components": rm.get("components").unwrap_or(&serde_json::json!([])).clone()
         }))
     } else {
         None
@@ -326,10 +859,26 @@ fn map_message(d: &Value) -> Value {
         "attachments": attachments,
         "referenced_message": referenced_message,
         "message_snapshots": message_snapshots,
-        "kind": kind
+        "kind": kind,
+        "reactions": map_reactions(d),
+        "components": components
     })
 }
 
+fn map_reactions(d: &Value) -> Value {
+    // Raw reaction { count, me, emoji: { id, name } } を SimpleReaction 形式に変換
+    let reactions = d["reactions"].as_array().map(|arr| arr.iter().map(|r| {
+        serde_json::json!({
+            "emoji_name": r["emoji"]["name"],
+            "emoji_id": r["emoji"]["id"],
+            "count": r["count"].as_u64().unwrap_or(0),
+            "me": r["me"].as_bool().unwrap_or(false),
+        })
+    }).collect::<Vec<_>>()).unwrap_or_default();
+
+    Value::Array(reactions)
+}
+
 // --- Gateway イベントハンドラー ---
 
 /// PRESENCE_UPDATE イベント処理
@@ -366,6 +915,8 @@ fn handle_presence_update(app: &AppHandle, d: &Value) {
                 username: d["user"]["username"].as_str().unwrap_or("Unknown").to_string(),
                 discriminator: d["user"]["discriminator"].as_str().unwrap_or("0").to_string(),
                 avatar: d["user"]["avatar"].as_str().map(|s| s.to_string()),
+                global_name: d["user"]["global_name"].as_str().map(|s| s.to_string()),
+                bot: d["user"]["bot"].as_bool().unwrap_or(false),
             };
             
             let activities_vec: Vec<crate::services::models::Activity> = d["activities"]
@@ -402,6 +953,8 @@ fn handle_voice_state_update(app: &AppHandle, d: &Value) {
     let self_deaf = d["self_deaf"].as_bool().unwrap_or(false);
     let mute = d["mute"].as_bool().unwrap_or(false);
     let deaf = d["deaf"].as_bool().unwrap_or(false);
+    let self_stream = d["self_stream"].as_bool().unwrap_or(false);
+    let self_video = d["self_video"].as_bool().unwrap_or(false);
     
     // GuildStateに保存
     if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
@@ -414,6 +967,8 @@ fn handle_voice_state_update(app: &AppHandle, d: &Value) {
                 self_deaf,
                 mute,
                 deaf,
+                self_stream,
+                self_video,
             };
             store.update_voice_state(&guild_id, voice_state);
         }
@@ -428,6 +983,8 @@ fn handle_voice_state_update(app: &AppHandle, d: &Value) {
         "self_deaf": self_deaf,
         "mute": mute,
         "deaf": deaf,
+        "self_stream": self_stream,
+        "self_video": self_video,
     });
     let _ = app.emit("voice_state_update", payload);
 }
@@ -446,6 +1003,19 @@ fn handle_typing_start(app: &AppHandle, d: &Value) {
         "timestamp": timestamp,
     });
     let _ = app.emit("typing_start", payload);
+
+    // 入力中ユーザーをまとめた typing_update も流し、期限切れ時にもう一度流す
+    let Some(typing) = app.try_state::<TypingState>() else { return };
+    emit_typing_update(app, &channel_id, typing.start(&channel_id, &user_id));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // 期限ちょうどだと Instant の誤差で残ることがあるので少し余裕を持たせる
+        tokio::time::sleep(TYPING_TIMEOUT + Duration::from_millis(100)).await;
+        if let Some(user_ids) = app.try_state::<TypingState>().and_then(|t| t.expire(&channel_id)) {
+            emit_typing_update(&app, &channel_id, user_ids);
+        }
+    });
 }
 
 /// GUILD_MEMBER_LIST_UPDATE (OP 14 レスポンス) 処理
@@ -497,6 +1067,406 @@ fn handle_member_list_update(app: &AppHandle, d: &Value) {
     let _ = app.emit("member_list_update", payload);
 }
 
+/// メンバーオブジェクトの avatar (サーバー専用アバター) からCDN URLを生成
+fn guild_avatar_url(guild_id: &str, user_id: &str, member_data: &Value) -> Option<String> {
+    member_data["avatar"].as_str().map(|hash| {
+        // https://cdn.discordapp.com/guilds/{guild_id}/users/{user_id}/avatars/{hash}.png
+        crate::services::config::cdn_asset_url(&format!("guilds/{}/users/{}", guild_id, user_id), "avatars", hash)
+    })
+}
+
+/// presences 配列を user.id で引けるようにする
+fn index_presences(presences: &Value) -> HashMap<String, &Value> {
+    presences
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|p| {
+            p["user"]["id"].as_str().map(|id| (id.to_string(), p))
+        }).collect())
+        .unwrap_or_default()
+}
+
+/// メンバーオブジェクトをストアに保存 (GUILD_MEMBERS_CHUNK / GUILD_CREATE 共通)
+/// プレゼンスが無い場合は既存のプレゼンスを維持する
+fn upsert_member_value(
+    store: &mut GuildMemberStore,
+    guild_id: &str,
+    member_data: &Value,
+    presences: &HashMap<String, &Value>,
+) -> bool {
+    let user_data = &member_data["user"];
+    let user_id = user_data["id"].as_str().unwrap_or("").to_string();
+    if user_id.is_empty() {
+        return false;
+    }
+
+    let user = crate::services::models::DiscordUser {
+        id: user_id.clone(),
+        username: user_data["username"].as_str().unwrap_or("Unknown").to_string(),
+        discriminator: user_data["discriminator"].as_str().unwrap_or("0").to_string(),
+        avatar: user_data["avatar"].as_str().map(|s| s.to_string()),
+        global_name: user_data["global_name"].as_str().map(|s| s.to_string()),
+        bot: user_data["bot"].as_bool().unwrap_or(false),
+    };
+
+    let roles: Vec<String> = member_data["roles"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|r| r.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    let existing = store.members.get(guild_id).and_then(|m| m.get(&user_id)).cloned();
+    let (status, activities, client_status) = match presences.get(&user_id) {
+        Some(p) => (
+            p["status"].as_str().unwrap_or("offline").to_string(),
+            p["activities"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|a| serde_json::from_value(a.clone()).ok()).collect())
+                .unwrap_or_default(),
+            serde_json::from_value(p["client_status"].clone()).unwrap_or_default(),
+        ),
+        None => match existing {
+            Some(m) => (m.status, m.activities, m.client_status),
+            None => ("offline".to_string(), vec![], Default::default()),
+        },
+    };
+
+    store.upsert_member(guild_id, crate::services::models::MemberWithPresence {
+        avatar_url: crate::services::guild_state::user_avatar_url(&user),
+        user,
+        roles,
+        nick: member_data["nick"].as_str().map(|s| s.to_string()),
+        guild_avatar: guild_avatar_url(guild_id, &user_id, member_data),
+        joined_at: member_data["joined_at"].as_str().unwrap_or("").to_string(),
+        status,
+        activities,
+        client_status,
+    });
+    true
+}
+
+/// GUILD_MEMBERS_CHUNK (OP 8 レスポンス) 処理
+fn handle_guild_members_chunk(app: &AppHandle, d: &Value) {
+    let guild_id = d["guild_id"].as_str().unwrap_or("").to_string();
+    let chunk_index = d["chunk_index"].as_u64().unwrap_or(0) as u32;
+    let chunk_count = d["chunk_count"].as_u64().unwrap_or(1) as u32;
+
+    // presences は members とは別配列で届く (user.id で紐付け)
+    let presences = index_presences(&d["presences"]);
+
+    let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() else {
+        return;
+    };
+    let Ok(mut store) = state.lock() else {
+        return;
+    };
+
+    let mut count = 0;
+    if let Some(members) = d["members"].as_array() {
+        for member_data in members {
+            if upsert_member_value(&mut store, &guild_id, member_data, &presences) {
+                count += 1;
+            }
+        }
+    }
+
+    let complete = store.record_member_chunk(&guild_id, chunk_count);
+    drop(store);
+
+    println!("[Gateway] GUILD_MEMBERS_CHUNK {}/{} for guild {} ({} members)", chunk_index + 1, chunk_count, guild_id, count);
+
+    if complete {
+        let payload = serde_json::json!({
+            "guild_id": guild_id,
+            "chunk_count": chunk_count,
+        });
+        let _ = app.emit("members_chunk_complete", payload);
+    }
+}
+
+/// READY の read_state (既読位置) をローカルの read_states テーブルに反映
+/// ユーザーアカウントでは { entries: [...] } 形式、古い形式では配列で届く
+fn handle_ready_read_state(app: &AppHandle, read_state: &Value) {
+    let Some(entries) = read_state["entries"].as_array().or_else(|| read_state.as_array()) else {
+        return;
+    };
+
+    let read_states: Vec<crate::store::read_states::ReadState> = entries.iter()
+        // read_state_type 0 以外 (通知センター等) はチャンネルの既読ではない
+        .filter(|e| e["read_state_type"].as_u64().unwrap_or(0) == 0)
+        .filter_map(|e| {
+            Some(crate::store::read_states::ReadState {
+                channel_id: e["id"].as_str()?.to_string(),
+                last_read_id: e["last_message_id"].as_str().map(|s| s.to_string()),
+                mention_count: e["mention_count"].as_u64().unwrap_or(0) as u32,
+            })
+        })
+        .collect();
+
+    if let Some(db_state) = app.try_state::<crate::store::DatabaseState>() {
+        if let Ok(mut conn) = db_state.conn.lock() {
+            match crate::store::read_states::save_read_states(&mut conn, &read_states) {
+                Ok(_) => println!("[Gateway] Saved {} read state(s) from READY", read_states.len()),
+                Err(e) => println!("[Gateway] Failed to save read states: {}", e),
+            }
+        }
+    }
+}
+
+/// READY の user_guild_settings をギルドごとの通知設定としてストアに保存
+fn handle_ready_guild_settings(app: &AppHandle, guild_settings: &Value) {
+    let Some(entries) = guild_settings["entries"].as_array().or_else(|| guild_settings.as_array()) else {
+        return;
+    };
+
+    let settings: Vec<crate::services::models::GuildNotificationSettings> = entries.iter()
+        .filter_map(|e| serde_json::from_value(e.clone()).ok())
+        .collect();
+
+    if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
+        if let Ok(mut store) = state.lock() {
+            println!("[Gateway] Loaded notification settings for {} guild(s) from READY", settings.len());
+            store.set_notification_settings(settings);
+        }
+    }
+}
+
+/// READY の private_channels をDM一覧として通知 (REST の get_dms を省略するため)
+/// user オブジェクトの重複排除が有効な場合、recipients は recipient_ids + users で届くので復元する
+fn handle_ready_private_channels(app: &AppHandle, d: &Value) {
+    let Some(private_channels) = d["private_channels"].as_array() else {
+        return;
+    };
+
+    let users: HashMap<&str, &Value> = d["users"].as_array()
+        .map(|users| users.iter().filter_map(|u| Some((u["id"].as_str()?, u))).collect())
+        .unwrap_or_default();
+
+    let channels: Vec<crate::services::models::DiscordDMChannel> = private_channels.iter()
+        .filter_map(|c| {
+            let mut c = c.clone();
+            if c.get("recipients").is_none() {
+                let recipients: Vec<Value> = c["recipient_ids"].as_array()
+                    .map(|ids| ids.iter()
+                        .filter_map(|id| users.get(id.as_str()?).map(|u| (*u).clone()))
+                        .collect())
+                    .unwrap_or_default();
+                c["recipients"] = Value::Array(recipients);
+            }
+            serde_json::from_value(c).ok()
+        })
+        .collect();
+
+    let mut dms = crate::services::social::map_dm_channels(channels);
+    // READY の private_channels は順不同なので、最新メッセージ順に並べる
+    dms.sort_by(|a, b| {
+        let a_id = a.last_message_id.as_deref().and_then(|id| id.parse::<u64>().ok()).unwrap_or(0);
+        let b_id = b.last_message_id.as_deref().and_then(|id| id.parse::<u64>().ok()).unwrap_or(0);
+        b_id.cmp(&a_id)
+    });
+    let _ = app.emit("dms_ready", &dms);
+}
+
+/// GUILD_ROLE_CREATE / GUILD_ROLE_UPDATE 処理 - キャッシュ済みのロールを追加・更新
+fn handle_guild_role_update(app: &AppHandle, d: &Value) {
+    let guild_id = d["guild_id"].as_str().unwrap_or("").to_string();
+    let Ok(role) = serde_json::from_value::<crate::services::models::DiscordRole>(d["role"].clone()) else {
+        return;
+    };
+
+    if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
+        if let Ok(mut store) = state.lock() {
+            store.upsert_role(&guild_id, crate::services::social::map_role(role));
+        }
+    }
+    emit_roles_update(app, &guild_id);
+}
+
+/// GUILD_ROLE_DELETE 処理
+fn handle_guild_role_delete(app: &AppHandle, d: &Value) {
+    let guild_id = d["guild_id"].as_str().unwrap_or("").to_string();
+    let role_id = d["role_id"].as_str().unwrap_or("").to_string();
+
+    if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
+        if let Ok(mut store) = state.lock() {
+            store.remove_role(&guild_id, &role_id);
+        }
+    }
+    emit_roles_update(app, &guild_id);
+}
+
+/// ロール変更後の一覧をフロントエンドに通知 (メンバー名の色などを再描画させる)
+fn emit_roles_update(app: &AppHandle, guild_id: &str) {
+    let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() else {
+        return;
+    };
+    let roles = match state.lock() {
+        Ok(store) => store.get_roles(guild_id),
+        Err(_) => return,
+    };
+
+    let payload = serde_json::json!({
+        "guild_id": guild_id,
+        "roles": roles,
+    });
+    let _ = app.emit("roles_update", payload);
+}
+
+/// GUILD_CREATE 処理 - 接続時/参加時に届くギルドの初期データをストアに反映
+/// チャンネル・ロール・(大規模でなければ) メンバー/プレゼンス/ボイス状態が含まれる
+fn handle_guild_create(app: &AppHandle, d: &Value) {
+    let guild_id = d["id"].as_str().unwrap_or("").to_string();
+    if guild_id.is_empty() {
+        return;
+    }
+
+    let mut channels = crate::services::social::map_guild_channels(parse_channels(&d["channels"]));
+    // アクティブなスレッドは REST (get_channels) と同じく通常チャンネルの後ろに並べる
+    channels.extend(parse_channels(&d["threads"]).into_iter()
+        .map(|t| crate::services::social::map_channel(t, "Unknown Thread")));
+
+    let roles: Vec<crate::services::models::SimpleRole> = d["roles"]
+        .as_array()
+        .map(|arr| arr.iter()
+            .filter_map(|r| serde_json::from_value(r.clone()).ok())
+            .map(crate::services::social::map_role)
+            .collect())
+        .unwrap_or_default();
+
+    let presences = index_presences(&d["presences"]);
+
+    let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() else {
+        return;
+    };
+    let Ok(mut store) = state.lock() else {
+        return;
+    };
+
+    let channel_count = channels.len();
+    let role_count = roles.len();
+    store.set_guild_metadata(&guild_id, channels, roles);
+
+    let mut member_count = 0;
+    if let Some(members) = d["members"].as_array() {
+        for member_data in members {
+            if upsert_member_value(&mut store, &guild_id, member_data, &presences) {
+                member_count += 1;
+            }
+        }
+    }
+
+    // GUILD_CREATE 内の voice_states には guild_id が含まれない
+    if let Some(voice_states) = d["voice_states"].as_array() {
+        for vs in voice_states {
+            if let Ok(mut voice_state) = serde_json::from_value::<crate::services::models::VoiceState>(vs.clone()) {
+                voice_state.guild_id = Some(guild_id.clone());
+                store.update_voice_state(&guild_id, voice_state);
+            }
+        }
+    }
+    drop(store);
+
+    println!("[Gateway] GUILD_CREATE {} ({} channels, {} roles, {} members)", guild_id, channel_count, role_count, member_count);
+
+    let payload = serde_json::json!({
+        "guild_id": guild_id,
+        "channel_count": channel_count,
+        "role_count": role_count,
+        "member_count": member_count,
+    });
+    let _ = app.emit("guild_ready", payload);
+}
+
+/// チャンネル配列 (GUILD_CREATE の channels / threads 等) をパース
+fn parse_channels(value: &Value) -> Vec<crate::services::models::DiscordChannel> {
+    value
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|c| serde_json::from_value(c.clone()).ok()).collect())
+        .unwrap_or_default()
+}
+
+/// CHANNEL_CREATE / CHANNEL_UPDATE / THREAD_CREATE / THREAD_UPDATE 処理
+/// アーカイブされたスレッドは、REST (threads/active) と揃えてアクティブ一覧から外す
+fn handle_channel_upsert(app: &AppHandle, d: &Value) {
+    // guild_id が無いのは DM なので対象外
+    let Some(guild_id) = d["guild_id"].as_str() else {
+        return;
+    };
+    let Ok(channel) = serde_json::from_value::<crate::services::models::DiscordChannel>(d.clone()) else {
+        return;
+    };
+    let archived = channel.thread_metadata.as_ref().is_some_and(|m| m.archived);
+
+    if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
+        if let Ok(mut store) = state.lock() {
+            if archived {
+                store.remove_channel(guild_id, &channel.id);
+            } else {
+                store.upsert_channel(guild_id, crate::services::social::map_channel(channel, "Unknown"));
+            }
+        }
+    }
+    emit_channels_update(app, guild_id, vec![]);
+}
+
+/// CHANNEL_DELETE / THREAD_DELETE 処理
+fn handle_channel_delete(app: &AppHandle, d: &Value) {
+    let Some(guild_id) = d["guild_id"].as_str() else {
+        return;
+    };
+    let channel_id = d["id"].as_str().unwrap_or("");
+
+    let removed = match app.try_state::<crate::services::guild_state::GuildStateHandle>() {
+        Some(state) => match state.lock() {
+            Ok(mut store) => store.remove_channel(guild_id, channel_id),
+            Err(_) => return,
+        },
+        None => return,
+    };
+    emit_channels_update(app, guild_id, removed);
+}
+
+/// THREAD_LIST_SYNC 処理 (チャンネルへのアクセス権を得たときなどにアクティブなスレッドが届く)
+fn handle_thread_list_sync(app: &AppHandle, d: &Value) {
+    let Some(guild_id) = d["guild_id"].as_str() else {
+        return;
+    };
+    // channel_ids が無い場合はギルド全体の同期
+    let parent_ids: Option<Vec<String>> = d["channel_ids"].as_array().map(|ids| {
+        ids.iter().filter_map(|id| id.as_str().map(|s| s.to_string())).collect()
+    });
+    let threads = parse_channels(&d["threads"]).into_iter()
+        .map(|t| crate::services::social::map_channel(t, "Unknown Thread"))
+        .collect();
+
+    if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
+        if let Ok(mut store) = state.lock() {
+            store.sync_threads(guild_id, parent_ids.as_deref(), threads);
+        }
+    }
+    emit_channels_update(app, guild_id, vec![]);
+}
+
+/// チャンネル変更後の一覧をフロントエンドに通知 (removed_ids は削除されたチャンネル)
+fn emit_channels_update(app: &AppHandle, guild_id: &str, removed_ids: Vec<String>) {
+    let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() else {
+        return;
+    };
+    let channels = match state.lock() {
+        Ok(store) => store.get_channels(guild_id),
+        Err(_) => return,
+    };
+    // ストアに一覧が無いギルドは REST で取得したものをそのまま使う
+    let Some(channels) = channels else {
+        return;
+    };
+
+    let payload = serde_json::json!({
+        "guild_id": guild_id,
+        "channels": channels,
+        "removed_ids": removed_ids,
+    });
+    let _ = app.emit("channels_update", payload);
+}
+
 /// メンバーアイテムを処理してストアに保存
 fn process_member_item(app: &AppHandle, guild_id: &str, member_data: &Value) {
     let user_data = &member_data["user"];
@@ -511,6 +1481,8 @@ fn process_member_item(app: &AppHandle, guild_id: &str, member_data: &Value) {
         username: user_data["username"].as_str().unwrap_or("Unknown").to_string(),
         discriminator: user_data["discriminator"].as_str().unwrap_or("0").to_string(),
         avatar: user_data["avatar"].as_str().map(|s| s.to_string()),
+        global_name: user_data["global_name"].as_str().map(|s| s.to_string()),
+        bot: user_data["bot"].as_bool().unwrap_or(false),
     };
     
     let roles: Vec<String> = member_data["roles"]
@@ -519,6 +1491,7 @@ fn process_member_item(app: &AppHandle, guild_id: &str, member_data: &Value) {
         .unwrap_or_default();
     
     let nick = member_data["nick"].as_str().map(|s| s.to_string());
+    let guild_avatar = guild_avatar_url(guild_id, &user_id, member_data);
     let joined_at = member_data["joined_at"].as_str().unwrap_or("").to_string();
     
     // プレゼンス情報（GUILD_MEMBER_LIST_UPDATEにはプレゼンスが含まれる場合がある）
@@ -537,9 +1510,11 @@ fn process_member_item(app: &AppHandle, guild_id: &str, member_data: &Value) {
     if let Some(state) = app.try_state::<crate::services::guild_state::GuildStateHandle>() {
         if let Ok(mut store) = state.lock() {
             let member_with_presence = crate::services::models::MemberWithPresence {
+                avatar_url: crate::services::guild_state::user_avatar_url(&user),
                 user,
                 roles,
                 nick,
+                guild_avatar,
                 joined_at,
                 status,
                 activities,
@@ -1,6 +1,9 @@
-use tauri::State;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
 use crate::services::state::DiscordState;
 use crate::services::identity;
+use crate::services::config;
+use crate::services::error::AppError;
 
 #[derive(serde::Serialize)]
 pub struct LoginResponse {
@@ -12,7 +15,7 @@ pub struct LoginResponse {
 }
 
 #[tauri::command]
-pub async fn init_client(token: String, state: State<'_, DiscordState>) -> Result<LoginResponse, String> {
+pub async fn init_client(token: String, state: State<'_, DiscordState>) -> Result<LoginResponse, AppError> {
     
     // Call pure service
     let (client, user) = identity::login(token).await?;
@@ -20,13 +23,13 @@ pub async fn init_client(token: String, state: State<'_, DiscordState>) -> Resul
     // Update state
     {
         let mut c = state.client.lock().unwrap();
-        *c = Some(client);
+        *c = Some(state.api_client(client));
+        state.client_generation.fetch_add(1, Ordering::SeqCst);
     }
+    state.set_bot(user.bot);
 
     // Convert Avatar Hash to URL
-    let avatar_url = user.avatar.map(|hash| {
-        format!("https://cdn.discordapp.com/avatars/{}/{}.png", user.id, hash)
-    });
+    let avatar_url = user.avatar.map(|hash| config::cdn_asset_url("avatars", &user.id, &hash));
 
     Ok(LoginResponse {
         message: format!("Logged in as: {}#{}", user.username, user.discriminator),
@@ -36,3 +39,81 @@ pub async fn init_client(token: String, state: State<'_, DiscordState>) -> Resul
         avatar: avatar_url,
     })
 }
+
+/// 401 の確認中フラグ (同時に複数のリクエストが 401 になっても確認は1回だけにする)
+static AUTH_CHECK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// トークン失効時の処理を登録 (setup から呼ぶ)
+/// REST が 401 を返したらクライアントを破棄し、フロントに auth_expired を通知して再ログインを促す
+pub fn init_auth_expiry_watch(app: &AppHandle) {
+    let Some(state) = app.try_state::<DiscordState>() else { return };
+    let app = app.clone();
+    state.set_unauthorized_handler(move || {
+        if AUTH_CHECK_RUNNING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            clear_client_if_expired(&app).await;
+            AUTH_CHECK_RUNNING.store(false, Ordering::SeqCst);
+        });
+    });
+}
+
+/// 401 は再ログイン前の古いトークンで送ったリクエストから返ることもあるので、
+/// 現在のクライアントでもトークンが拒否される場合だけ、そのクライアントを破棄して Gateway も止める
+async fn clear_client_if_expired(app: &AppHandle) {
+    let Some(state) = app.try_state::<DiscordState>() else { return };
+    let Some((client, generation)) = state.client.lock().ok().and_then(|c| {
+        c.as_ref().cloned().map(|client| (client, state.client_generation.load(Ordering::SeqCst)))
+    }) else {
+        return;
+    };
+
+    if !identity::is_token_rejected(&client).await {
+        return;
+    }
+
+    // 確認中に再ログインされていたら、新しいクライアントは消さない
+    let cleared = state.client.lock().map(|mut c| {
+        if state.client_generation.load(Ordering::SeqCst) == generation {
+            c.take().is_some()
+        } else {
+            false
+        }
+    }).unwrap_or(false);
+    if cleared {
+        state.set_bot(false);
+        crate::bridge::gateway::shutdown_gateway(app);
+        println!("[Identity] Token expired or revoked, client cleared");
+        let _ = app.emit("auth_expired", ());
+    }
+}
+
+/// トークンを検証し、どのアカウントのものかを返す (ログイン状態は変更しない)
+#[tauri::command]
+pub async fn validate_token(token: String) -> Result<crate::services::models::DiscordUser, AppError> {
+    identity::validate_token(token).await
+}
+
+/// Discord APIのベースURLを上書き (空文字でデフォルトに戻す)
+#[tauri::command]
+pub fn set_api_base(base: String) {
+    if base.is_empty() {
+        config::set_api_base(config::DEFAULT_API_BASE.to_string());
+    } else {
+        config::set_api_base(base);
+    }
+}
+
+/// Gateway Identify の設定を取得
+#[tauri::command]
+pub fn get_identify_config() -> config::IdentifyConfig {
+    config::identify_config()
+}
+
+/// Gateway Identify の設定を上書き (None でデフォルトに戻す / 次回接続から反映)
+#[tauri::command]
+pub fn set_identify_config(identify: Option<config::IdentifyConfig>) {
+    config::set_identify_config(identify.unwrap_or_default());
+}
@@ -3,6 +3,7 @@ use crate::services::models::{SimpleMessage};
 use crate::services::state::DiscordState;
 use crate::store::DatabaseState as DbState;
 use crate::services::social;
+use crate::services::error::AppError;
 
 
 
@@ -13,7 +14,7 @@ pub async fn fetch_messages(
     channel_id: String,
     state: State<'_, DiscordState>,
     db_state: State<'_, DbState>,
-) -> Result<Vec<SimpleMessage>, String> {
+) -> Result<Vec<SimpleMessage>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -1,11 +1,15 @@
 use tauri::State;
-use crate::services::models::{SimpleGuild, SimpleChannel, SimpleMessage, SimpleRole, SimpleMember, DiscordUser};
+use crate::services::models::{SimpleGuild, GuildDetail, SimpleChannel, SimpleMessage, SimpleRole, SimpleMember, SimpleInvite, SimpleAuditLogEntry, SimpleThreadMember, SearchResult, DiscordUser, DiscordUserSettings, VoiceRegion};
 use crate::services::state::DiscordState;
 use crate::services::social;
+use crate::services::error::{AppError, ErrorKind};
 use crate::store::DatabaseState as DbState; 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 #[tauri::command]
-pub async fn get_guilds(state: State<'_, DiscordState>) -> Result<Vec<SimpleGuild>, String> {
+pub async fn get_guilds(state: State<'_, DiscordState>) -> Result<Vec<SimpleGuild>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -15,7 +19,7 @@ pub async fn get_guilds(state: State<'_, DiscordState>) -> Result<Vec<SimpleGuil
 }
 
 #[tauri::command]
-pub async fn get_dms(state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, String> {
+pub async fn get_dms(state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -25,7 +29,7 @@ pub async fn get_dms(state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel
 }
 
 #[tauri::command]
-pub async fn get_current_user(state: State<'_, DiscordState>) -> Result<DiscordUser, String> {
+pub async fn get_current_user(state: State<'_, DiscordState>) -> Result<DiscordUser, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -35,23 +39,106 @@ pub async fn get_current_user(state: State<'_, DiscordState>) -> Result<DiscordU
 }
 
 #[tauri::command]
-pub async fn get_roles(guild_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleRole>, String> {
+pub async fn get_user_settings(state: State<'_, DiscordState>) -> Result<DiscordUserSettings, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_user_settings(&client).await
+}
+
+/// ボイスリージョン一覧を取得
+#[tauri::command]
+pub async fn get_voice_regions(state: State<'_, DiscordState>) -> Result<Vec<VoiceRegion>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_voice_regions(&client).await
+}
+
+/// 単一ギルドの詳細を取得
+#[tauri::command]
+pub async fn get_guild(guild_id: String, state: State<'_, DiscordState>) -> Result<GuildDetail, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_guild(&client, guild_id).await
+}
+
+/// チャンネルのミュートをDiscord側の通知設定にも同期 (ローカルのミュートは set_channel_muted)
+#[tauri::command]
+pub async fn sync_channel_muted(guild_id: String, channel_id: String, muted: bool, state: State<'_, DiscordState>) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::update_channel_muted(&client, guild_id, channel_id, muted).await
+}
+
+#[tauri::command]
+pub async fn leave_guild(
+    guild_id: String,
+    state: State<'_, DiscordState>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>,
+    db_state: State<'_, DbState>,
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::leave_guild(&client, guild_id.clone()).await?;
+
+    // 脱退したサーバーのメンバー情報とキャッシュを破棄
+    if let Ok(mut store) = guild_state.lock() {
+        store.clear_guild(&guild_id);
+    }
+    if let Ok(conn) = db_state.conn.lock() {
+        let _ = crate::store::delete_guild_messages(&conn, &guild_id);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_roles(
+    guild_id: String,
+    state: State<'_, DiscordState>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>,
+) -> Result<Vec<SimpleRole>, AppError> {
     println!("[get_roles] Called for guild: {}", guild_id);
+
+    // GUILD_CREATE / GUILD_ROLE_UPDATE で保持しているロールがあればRESTを呼ばない
+    if let Some(roles) = guild_state.lock().ok().and_then(|store| store.get_roles(&guild_id)) {
+        return Ok(roles);
+    }
+
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
     };
 
-    let result = social::fetch_roles(&client, guild_id).await;
+    let result = social::fetch_roles(&client, guild_id.clone()).await;
     match &result {
-        Ok(roles) => println!("[get_roles] Fetched {} roles", roles.len()),
+        Ok(roles) => {
+            println!("[get_roles] Fetched {} roles", roles.len());
+            if let Ok(mut store) = guild_state.lock() {
+                store.set_roles(&guild_id, roles.clone());
+            }
+        }
         Err(e) => println!("[get_roles] Error: {}", e),
     }
     result
 }
 
 #[tauri::command]
-pub async fn get_members(guild_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleMember>, String> {
+pub async fn get_members(guild_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleMember>, AppError> {
     println!("[get_members] Called for guild: {}", guild_id);
     let client = {
         let c = state.client.lock().unwrap();
@@ -66,6 +153,151 @@ pub async fn get_members(guild_id: String, state: State<'_, DiscordState>) -> Re
     result
 }
 
+/// REST のメンバー一覧に Gateway ストアのプレゼンスを重ねて取得
+#[tauri::command]
+pub async fn get_members_merged(
+    guild_id: String,
+    state: State<'_, DiscordState>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>,
+) -> Result<Vec<crate::services::models::MemberWithPresence>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let members = social::fetch_members(&client, guild_id.clone()).await?;
+    let store = guild_state.lock().map_err(|e| e.to_string())?;
+    Ok(store.merge_members(&guild_id, members))
+}
+
+#[tauri::command]
+pub async fn kick_member(guild_id: String, user_id: String, reason: Option<String>, state: State<'_, DiscordState>) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::kick_member(&client, guild_id, user_id, reason).await
+}
+
+#[tauri::command]
+pub async fn ban_member(
+    guild_id: String,
+    user_id: String,
+    delete_message_seconds: Option<u32>,
+    reason: Option<String>,
+    state: State<'_, DiscordState>,
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::ban_member(&client, guild_id, user_id, delete_message_seconds.unwrap_or(0), reason).await
+}
+
+/// ニックネームを変更し、変更後のニックネームを返す (user_id: "@me" で自分)
+#[tauri::command]
+pub async fn set_nickname(
+    guild_id: String,
+    user_id: String,
+    nick: Option<String>,
+    state: State<'_, DiscordState>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>,
+) -> Result<Option<String>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let nick = nick.filter(|n| !n.is_empty());
+    let member = social::set_nickname(&client, guild_id.clone(), user_id, nick).await?;
+
+    // Gatewayの GUILD_MEMBER_UPDATE を待たずにストアへ反映
+    if let Some(user) = &member.user {
+        if let Ok(mut store) = guild_state.lock() {
+            store.update_member_nick(&guild_id, &user.id, member.nick.clone());
+        }
+    }
+
+    Ok(member.nick)
+}
+
+#[tauri::command]
+pub async fn add_member_role(
+    guild_id: String,
+    user_id: String,
+    role_id: String,
+    reason: Option<String>,
+    state: State<'_, DiscordState>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>,
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::add_member_role(&client, guild_id.clone(), user_id.clone(), role_id.clone(), reason).await?;
+
+    if let Ok(mut store) = guild_state.lock() {
+        store.set_member_role(&guild_id, &user_id, &role_id, true);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_member_role(
+    guild_id: String,
+    user_id: String,
+    role_id: String,
+    reason: Option<String>,
+    state: State<'_, DiscordState>,
+    guild_state: State<'_, crate::services::guild_state::GuildStateHandle>,
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::remove_member_role(&client, guild_id.clone(), user_id.clone(), role_id.clone(), reason).await?;
+
+    if let Ok(mut store) = guild_state.lock() {
+        store.set_member_role(&guild_id, &user_id, &role_id, false);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_invites(channel_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleInvite>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_invites(&client, channel_id).await
+}
+
+/// 監査ログを取得 (limit 省略時は50件)
+#[tauri::command]
+pub async fn get_audit_log(guild_id: String, action_type: Option<u32>, limit: Option<u32>, state: State<'_, DiscordState>) -> Result<Vec<SimpleAuditLogEntry>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_audit_log(&client, guild_id, action_type, limit.unwrap_or(50)).await
+}
+
+#[tauri::command]
+pub async fn create_invite(channel_id: String, max_age: u32, max_uses: u32, state: State<'_, DiscordState>) -> Result<SimpleInvite, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::create_invite(&client, channel_id, max_age, max_uses).await
+}
+
 /// Gateway経由で収集したメンバー情報を取得（プレゼンス付き）
 #[tauri::command]
 pub fn get_guild_members_from_store(
@@ -76,6 +308,28 @@ pub fn get_guild_members_from_store(
     Ok(store.get_members(&guild_id))
 }
 
+/// 指定ステータスのメンバーのみ取得 (例: ["online", "idle", "dnd"] でオンラインのみ)
+#[tauri::command]
+pub fn get_guild_members_by_status(
+    guild_id: String,
+    statuses: Vec<String>,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<Vec<crate::services::models::MemberWithPresence>, String> {
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_members_by_status(&guild_id, &statuses))
+}
+
+/// ストアのメンバー情報を破棄 (subscribe_member_list で再購読する前に呼び、退出済みメンバーが残らないようにする)
+#[tauri::command]
+pub fn clear_guild_members(
+    guild_id: String,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<(), String> {
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.clear_members(&guild_id);
+    Ok(())
+}
+
 /// Gateway経由で収集したボイス状態を取得
 #[tauri::command]
 pub fn get_voice_states(
@@ -86,8 +340,62 @@ pub fn get_voice_states(
     Ok(store.get_voice_states(&guild_id))
 }
 
+/// ボイスチャンネルの参加者一覧を取得 (ボイス状態 + メンバー情報)
+#[tauri::command]
+pub fn get_voice_channel_participants(
+    guild_id: String,
+    channel_id: String,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<Vec<crate::services::models::VoiceParticipant>, String> {
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_voice_channel_participants(&guild_id, &channel_id))
+}
+
+/// GUILD_CREATE で受信したチャンネル一覧を取得 (未受信なら None → REST の get_channels を使う)
+#[tauri::command]
+pub fn get_channels_from_store(
+    guild_id: String,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<Option<Vec<SimpleChannel>>, String> {
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_channels(&guild_id))
+}
+
+/// GUILD_CREATE で受信したロール一覧を取得 (未受信なら None → REST の get_roles を使う)
+#[tauri::command]
+pub fn get_roles_from_store(
+    guild_id: String,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<Option<Vec<SimpleRole>>, String> {
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_roles(&guild_id))
+}
+
+/// メッセージ本文のメンションをストアのメンバー・ロール・チャンネル名に置き換える
+#[tauri::command]
+pub fn resolve_mentions(
+    guild_id: String,
+    content: String,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<String, String> {
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(store.resolve_mentions(&guild_id, &content))
+}
+
+/// READY で受信したギルドの通知設定を取得 (未受信なら None, DM は "@me")
+#[tauri::command]
+pub fn get_guild_notification_settings(
+    guild_id: String,
+    state: State<'_, crate::services::guild_state::GuildStateHandle>
+) -> Result<Option<crate::services::models::GuildNotificationSettings>, String> {
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_notification_settings(&guild_id))
+}
+
+/// ギルドのチャンネル一覧を取得
+/// types を指定した場合はその種別 (kind: "Voice", "Forum" など) のチャンネルのみ返す
 #[tauri::command]
-pub async fn get_channels(guild_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, String> {
+pub async fn get_channels(guild_id: String, types: Option<Vec<String>>, state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -101,11 +409,15 @@ pub async fn get_channels(guild_id: String, state: State<'_, DiscordState>) -> R
         Err(e) => println!("Failed to fetch active threads: {}", e),
     }
 
+    if let Some(types) = types {
+        channels.retain(|c| types.iter().any(|t| t == &c.kind));
+    }
+
     Ok(channels)
 }
 
 #[tauri::command]
-pub async fn get_archived_threads(channel_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, String> {
+pub async fn get_archived_threads(channel_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -115,7 +427,7 @@ pub async fn get_archived_threads(channel_id: String, state: State<'_, DiscordSt
 }
 
 #[tauri::command]
-pub async fn get_forum_active_threads(guild_id: String, channel_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, String> {
+pub async fn get_forum_active_threads(guild_id: String, channel_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleChannel>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -124,6 +436,46 @@ pub async fn get_forum_active_threads(guild_id: String, channel_id: String, stat
     social::fetch_forum_active_threads(&client, guild_id, channel_id).await
 }
 
+#[tauri::command]
+pub async fn create_thread(channel_id: String, name: String, message_id: Option<String>, state: State<'_, DiscordState>) -> Result<SimpleChannel, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::create_thread(&client, channel_id, name, message_id).await
+}
+
+#[tauri::command]
+pub async fn join_thread(channel_id: String, state: State<'_, DiscordState>) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::join_thread(&client, channel_id).await
+}
+
+#[tauri::command]
+pub async fn leave_thread(channel_id: String, state: State<'_, DiscordState>) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::leave_thread(&client, channel_id).await
+}
+
+#[tauri::command]
+pub async fn get_thread_members(channel_id: String, state: State<'_, DiscordState>) -> Result<Vec<SimpleThreadMember>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_thread_members(&client, channel_id).await
+}
+
 #[tauri::command]
 pub async fn get_messages(
     guild_id: String,
@@ -131,7 +483,7 @@ pub async fn get_messages(
     before_id: Option<String>, 
     state: State<'_, DiscordState>,
     db_state: State<'_, DbState>,
-) -> Result<Vec<SimpleMessage>, String> {
+) -> Result<Vec<SimpleMessage>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -148,18 +500,149 @@ pub async fn get_messages(
     Ok(messages)
 }
 
+/// 取得するページ数の上限 (100件 x 10ページ)。これ以上の差分は通常の履歴取得に任せる
+const NEW_MESSAGES_MAX_PAGES: u32 = 10;
+
+/// キャッシュ済みの最新メッセージ以降の差分だけを取得して保存 (再接続・チャンネル表示時用)
+/// キャッシュが空の場合は通常の最新50件を取得する
+#[tauri::command]
+pub async fn fetch_new_messages(
+    guild_id: String,
+    channel_id: String,
+    state: State<'_, DiscordState>,
+    db_state: State<'_, DbState>,
+) -> Result<Vec<SimpleMessage>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let newest = {
+        let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
+        crate::store::newest_cached_message_id(&conn, &channel_id)?
+    };
+
+    let Some(after_id) = newest else {
+        let messages = social::fetch_messages_with_guid(&client, guild_id, channel_id, None).await?;
+        let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
+        crate::store::save_messages(&conn, &messages).ok();
+        return Ok(messages);
+    };
+
+    let mut new_messages = sync_messages_after(&client, &db_state.conn, guild_id, channel_id, after_id).await?;
+
+    // get_messages と同じく新しい順で返す
+    new_messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(new_messages)
+}
+
+/// after_id より新しいメッセージをページングしながら取得して保存する
+/// fetch_new_messages と Gateway 再接続時の取りこぼし補完で共用
+pub async fn sync_messages_after(
+    client: &crate::services::state::ApiClient,
+    conn: &std::sync::Mutex<rusqlite::Connection>,
+    guild_id: String,
+    channel_id: String,
+    mut after_id: String,
+) -> Result<Vec<SimpleMessage>, AppError> {
+    let mut new_messages: Vec<SimpleMessage> = Vec::new();
+    for _ in 0..NEW_MESSAGES_MAX_PAGES {
+        let batch = social::fetch_messages_after(client, guild_id.clone(), channel_id.clone(), after_id.clone()).await?;
+        if batch.is_empty() {
+            break;
+        }
+
+        {
+            let conn = conn.lock().map_err(|e| e.to_string())?;
+            crate::store::save_messages(&conn, &batch).ok();
+        }
+
+        // 次ページのカーソルはバッチ内で最大のSnowflake
+        if let Some(max_id) = batch.iter().map(|m| &m.id).max_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b))) {
+            after_id = max_id.clone();
+        }
+
+        let full_page = batch.len() >= 100;
+        new_messages.extend(batch);
+        if !full_page {
+            break;
+        }
+    }
+
+    Ok(new_messages)
+}
+
+#[tauri::command]
+pub async fn get_messages_around(
+    guild_id: String,
+    channel_id: String,
+    message_id: String,
+    limit: Option<u32>,
+    state: State<'_, DiscordState>,
+    db_state: State<'_, DbState>,
+) -> Result<Vec<SimpleMessage>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let messages = social::fetch_messages_around(&client, guild_id, channel_id, message_id, limit.unwrap_or(50)).await?;
+
+    // Save to Cache (Store)
+    {
+        let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
+        crate::store::save_messages(&conn, &messages).ok();
+    }
+
+    Ok(messages)
+}
+
 #[tauri::command]
-pub async fn send_message(guild_id: String, channel_id: String, content: String, reply_to: Option<String>, state: State<'_, DiscordState>) -> Result<SimpleMessage, String> {
+pub async fn send_message(
+    guild_id: String,
+    channel_id: String,
+    content: String,
+    reply_to: Option<String>,
+    state: State<'_, DiscordState>,
+    recent_sent: State<'_, crate::bridge::gateway::RecentSentMessages>,
+) -> Result<SimpleMessage, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let message = social::send_message(&client, guild_id, channel_id, content, reply_to).await?;
+    // Gateway の MESSAGE_CREATE エコーと照合できるよう記録しておく
+    recent_sent.record(message.id.clone());
+    Ok(message)
+}
+
+/// 埋め込み付きでメッセージを送信 (BOTトークンでログインしている場合のみ)
+#[tauri::command]
+pub async fn send_message_with_embed(
+    guild_id: String,
+    channel_id: String,
+    content: String,
+    embeds: Vec<crate::services::models::DiscordEmbed>,
+    state: State<'_, DiscordState>,
+    recent_sent: State<'_, crate::bridge::gateway::RecentSentMessages>,
+) -> Result<SimpleMessage, AppError> {
+    // ユーザーアカウントでは embeds が黙って捨てられるので、送る前にエラーにする
+    if !state.is_bot() {
+        return Err(AppError::forbidden("Sending embeds requires a bot token"));
+    }
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
     };
 
-    social::send_message(&client, guild_id, channel_id, content, reply_to).await
+    let message = social::send_message_with_embed(&client, guild_id, channel_id, content, embeds).await?;
+    recent_sent.record(message.id.clone());
+    Ok(message)
 }
 
 #[tauri::command]
-pub async fn delete_message(channel_id: String, message_id: String, state: State<'_, DiscordState>) -> Result<(), String> {
+pub async fn delete_message(channel_id: String, message_id: String, state: State<'_, DiscordState>) -> Result<(), AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -168,13 +651,101 @@ pub async fn delete_message(channel_id: String, message_id: String, state: State
     social::delete_message(&client, channel_id, message_id).await
 }
 
+/// リアクションしたユーザー一覧を取得 (ホバー時のツールチップ用)
+#[tauri::command]
+pub async fn get_reaction_users(channel_id: String, message_id: String, emoji: String, state: State<'_, DiscordState>) -> Result<Vec<DiscordUser>, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::fetch_reaction_users(&client, channel_id, message_id, emoji).await
+}
+
+/// メッセージのリアクションをすべて削除
+#[tauri::command]
+pub async fn remove_all_reactions(channel_id: String, message_id: String, state: State<'_, DiscordState>) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::remove_all_reactions(&client, channel_id, message_id).await
+}
+
+/// 指定した絵文字のリアクションを削除
+#[tauri::command]
+pub async fn remove_reaction_emoji(channel_id: String, message_id: String, emoji: String, state: State<'_, DiscordState>) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::remove_reaction_emoji(&client, channel_id, message_id, emoji).await
+}
+
+/// チャンネルをメッセージまで既読にする (Discordへ通知し、ローカルの既読状態も更新)
+#[tauri::command]
+pub async fn ack_message(
+    channel_id: String,
+    message_id: String,
+    state: State<'_, DiscordState>,
+    db_state: State<'_, DbState>,
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    social::ack_message(&client, channel_id.clone(), message_id.clone()).await?;
+
+    let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
+    crate::store::read_states::upsert_read_state(&conn, &crate::store::read_states::ReadState {
+        channel_id,
+        last_read_id: Some(message_id),
+        mention_count: 0,
+    })?;
+    Ok(())
+}
+
+/// fetch_all_history の結果
+#[derive(serde::Serialize)]
+pub struct HistoryFetchResult {
+    pub total_fetched: u32,
+    pub completed: bool,  // チャンネルの先頭まで到達した
+    pub truncated: bool,  // 上限 (max_iterations) に達して打ち切った
+    pub cancelled: bool,  // cancel_history_fetch で中断された
+}
+
+/// 実行中の fetch_all_history のキャンセルフラグ (channel_id -> flag)
+#[derive(Default)]
+pub struct HistoryFetchCancel(pub Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>);
+
+/// 実行中の全履歴取得を中断する (取得中でなければ false)
+#[tauri::command]
+pub fn cancel_history_fetch(channel_id: String, cancel_state: State<'_, HistoryFetchCancel>) -> Result<bool, String> {
+    let tokens = cancel_state.0.lock().map_err(|e| e.to_string())?;
+    match tokens.get(&channel_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 #[tauri::command]
 pub async fn fetch_all_history(
+    app: tauri::AppHandle,
     guild_id: String,
     channel_id: String,
+    max_iterations: Option<u32>,
     state: State<'_, DiscordState>,
     db_state: State<'_, DbState>,
-) -> Result<u32, String> {
+    cancel_state: State<'_, HistoryFetchCancel>,
+) -> Result<HistoryFetchResult, AppError> {
+    use tauri::Emitter;
+
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -187,16 +758,39 @@ pub async fn fetch_all_history(
     // For strict separation, Bridge should coordinate.
     // Let's implement the loop here using social service for fetching.
     
+    const MAX_RETRIES: u32 = 5;
+
     let mut total_fetched: u32 = 0;
     let mut before_id: Option<String> = None;
-    let max_iterations = 20;
+    let max_iterations = max_iterations.unwrap_or(20);
+    let mut completed = false;
+    let mut iterations = 0;
+    let mut retries = 0;
+    let mut cancelled = false;
+    // 回復できずに中断した原因 (フロントに返す)
+    let mut failure: Option<AppError> = None;
+
+    // 同じチャンネルで再実行された場合は新しいフラグに置き換える
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    cancel_state.0.lock().map_err(|e| e.to_string())?.insert(channel_id.clone(), cancel_flag.clone());
+
+    while iterations < max_iterations {
+        if cancel_flag.load(Ordering::SeqCst) {
+            println!("[fetch_all_history] Cancelled for channel {}", channel_id);
+            cancelled = true;
+            break;
+        }
 
-    for _ in 0..max_iterations {
         let messages = social::fetch_messages_with_guid(&client, guild_id.clone(), channel_id.clone(), before_id.clone()).await;
 
         match messages {
             Ok(msgs) => {
-                if msgs.is_empty() { break; }
+                retries = 0;
+                iterations += 1;
+                if msgs.is_empty() {
+                    completed = true;
+                    break;
+                }
                 
                 // Save to DB
                 {
@@ -206,38 +800,164 @@ pub async fn fetch_all_history(
 
                 total_fetched += msgs.len() as u32;
                 before_id = msgs.last().map(|m| m.id.clone());
+
+                let _ = app.emit("history-fetch-progress", serde_json::json!({
+                    "channel_id": channel_id,
+                    "count": total_fetched,
+                }));
+
+                // 1ページ50件未満 = 先頭に到達
+                if msgs.len() < 50 {
+                    completed = true;
+                    break;
+                }
+
                 tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
             },
-            Err(_) => break,
+            Err(e) => {
+                // 認証/権限エラーはリトライしても回復しないので即終了
+                if matches!(e.kind, ErrorKind::Unauthorized | ErrorKind::Forbidden | ErrorKind::NotFound) {
+                    println!("[fetch_all_history] Aborting: {}", e);
+                    failure = Some(e);
+                    break;
+                }
+                // 一時的なエラーは指数バックオフでリトライ (1s, 2s, 4s, ...)
+                if retries >= MAX_RETRIES {
+                    println!("[fetch_all_history] Giving up after {} retries: {}", retries, e);
+                    failure = Some(e);
+                    break;
+                }
+                let delay = 1000u64 << retries;
+                println!("[fetch_all_history] Error: {} (retry in {}ms)", e, delay);
+                retries += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            }
         }
     }
 
-    Ok(total_fetched)
+    if let Ok(mut tokens) = cancel_state.0.lock() {
+        if tokens.get(&channel_id).is_some_and(|flag| Arc::ptr_eq(flag, &cancel_flag)) {
+            tokens.remove(&channel_id);
+        }
+    }
+
+    // 取得済みの分はDBに保存されているので、原因だけを返す
+    if let Some(e) = failure {
+        return Err(e);
+    }
+
+    Ok(HistoryFetchResult {
+        total_fetched,
+        completed,
+        truncated: !completed && !cancelled && iterations >= max_iterations,
+        cancelled,
+    })
 }
 
 #[tauri::command]
 pub async fn search_discord_api(
     guild_id: String,
     query: String,
+    filters: Option<social::SearchFilters>,
     state: State<'_, DiscordState>,
     db_state: State<'_, DbState>,
-) -> Result<Vec<SimpleMessage>, String> {
+) -> Result<SearchResult, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
     };
 
-    let messages = social::search_discord(&client, guild_id, query).await?;
+    // query はフィルタの content として扱う (フィルタのみの検索では空文字)
+    let mut filters = filters.unwrap_or_default();
+    if !query.is_empty() {
+        filters.content = Some(query);
+    }
+
+    let result = social::search_discord(&client, guild_id, filters).await?;
 
     // Save to DB
     {
         let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
-        for m in &messages {
+        for m in &result.messages {
             crate::store::save_message(&conn, m).ok();
         }
     }
 
-    Ok(messages)
+    Ok(result)
+}
+
+/// ハイブリッド検索: ローカルFTSの結果を即座に返し、Discord検索の結果をマージして再通知
+/// - hybrid_search_local: ローカル結果 (即時)
+/// - hybrid_search_merged: ローカル + リモートの重複排除済み結果
+#[tauri::command]
+pub async fn hybrid_search(
+    app: tauri::AppHandle,
+    guild_id: String,
+    query: String,
+    state: State<'_, DiscordState>,
+    db_state: State<'_, DbState>,
+) -> Result<SearchResult, AppError> {
+    use tauri::Emitter;
+
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    // 1. ローカルFTS
+    let local = {
+        let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
+        crate::store::search_messages_local(&conn, &guild_id, &query)?
+    };
+    let _ = app.emit("hybrid_search_local", serde_json::json!({
+        "guild_id": guild_id,
+        "query": query,
+        "messages": local,
+    }));
+
+    // 2. Discord検索 (失敗してもローカル結果は返す)
+    let filters = social::SearchFilters {
+        content: Some(query.clone()),
+        ..Default::default()
+    };
+    let remote = match social::search_discord(&client, guild_id.clone(), filters).await {
+        Ok(r) => r,
+        Err(e) => {
+            println!("[hybrid_search] Remote search failed: {}", e);
+            SearchResult { messages: vec![], total_results: 0 }
+        }
+    };
+
+    if !remote.messages.is_empty() {
+        let conn = db_state.conn.lock().map_err(|e| e.to_string())?;
+        for m in &remote.messages {
+            crate::store::save_message(&conn, m).ok();
+        }
+    }
+
+    // 3. マージ (IDで重複排除、リモートの方が referenced_message 等を持つので優先)
+    let mut merged: std::collections::HashMap<String, SimpleMessage> = local.into_iter()
+        .map(|m| (m.id.clone(), m))
+        .collect();
+    for m in remote.messages {
+        merged.insert(m.id.clone(), m);
+    }
+    let mut messages: Vec<SimpleMessage> = merged.into_values().collect();
+    messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    let result = SearchResult {
+        total_results: remote.total_results.max(messages.len() as u32),
+        messages,
+    };
+
+    let _ = app.emit("hybrid_search_merged", serde_json::json!({
+        "guild_id": guild_id,
+        "query": query,
+        "messages": result.messages,
+        "total_results": result.total_results,
+    }));
+
+    Ok(result)
 }
 
 // =============================
@@ -248,7 +968,7 @@ pub async fn search_discord_api(
 pub async fn get_application_commands(
     guild_id: Option<String>,
     state: State<'_, DiscordState>
-) -> Result<Vec<social::ApplicationCommand>, String> {
+) -> Result<Vec<social::ApplicationCommand>, AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -265,7 +985,7 @@ pub async fn send_interaction(
     data: social::InteractionData,
     state: State<'_, DiscordState>,
     session_state: State<'_, crate::bridge::gateway::SessionState>
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let client = {
         let c = state.client.lock().unwrap();
         c.as_ref().cloned().ok_or("Client not initialized")?
@@ -278,3 +998,77 @@ pub async fn send_interaction(
 
     social::send_interaction(&client, channel_id, guild_id, application_id, data, session_id).await
 }
+
+/// スラッシュコマンドのオートコンプリートを要求
+/// 戻り値のナンスと一致する autocomplete_response イベントで候補が届く
+#[tauri::command]
+pub async fn send_autocomplete_interaction(
+    channel_id: String,
+    guild_id: Option<String>,
+    application_id: String,
+    data: social::InteractionData,
+    state: State<'_, DiscordState>,
+    session_state: State<'_, crate::bridge::gateway::SessionState>
+) -> Result<String, AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let session_id = {
+        let lock = session_state.0.lock().map_err(|e| e.to_string())?;
+        lock.as_ref().cloned().ok_or("Session ID not available (Gateway not ready)")?
+    };
+
+    social::send_autocomplete_interaction(&client, channel_id, guild_id, application_id, data, session_id).await
+}
+
+/// BOTメッセージのボタン押下・セレクトメニュー選択を送信
+/// application_id は通常メッセージを送信したBOTのユーザーID
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn send_component_interaction(
+    channel_id: String,
+    guild_id: Option<String>,
+    application_id: String,
+    message_id: String,
+    message_flags: Option<u64>,
+    data: social::ComponentInteractionData,
+    state: State<'_, DiscordState>,
+    session_state: State<'_, crate::bridge::gateway::SessionState>
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let session_id = {
+        let lock = session_state.0.lock().map_err(|e| e.to_string())?;
+        lock.as_ref().cloned().ok_or("Session ID not available (Gateway not ready)")?
+    };
+
+    social::send_component_interaction(&client, channel_id, guild_id, application_id, message_id, message_flags.unwrap_or(0), data, session_id).await
+}
+
+/// BOTが表示したモーダルの入力内容を送信
+#[tauri::command]
+pub async fn send_modal_submit(
+    channel_id: String,
+    guild_id: Option<String>,
+    application_id: String,
+    data: social::ModalSubmitData,
+    state: State<'_, DiscordState>,
+    session_state: State<'_, crate::bridge::gateway::SessionState>
+) -> Result<(), AppError> {
+    let client = {
+        let c = state.client.lock().unwrap();
+        c.as_ref().cloned().ok_or("Client not initialized")?
+    };
+
+    let session_id = {
+        let lock = session_state.0.lock().map_err(|e| e.to_string())?;
+        lock.as_ref().cloned().ok_or("Session ID not available (Gateway not ready)")?
+    };
+
+    social::send_modal_submit(&client, channel_id, guild_id, application_id, data, session_id).await
+}
@@ -1,5 +1,5 @@
 use tauri::{Window, State, PhysicalPosition, PhysicalSize};
-use crate::services::desktop::{self, MonitorInfo, ClipboardState};
+use crate::services::desktop::{self, MonitorInfo, ClipboardState, InputQueue};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -78,24 +78,99 @@ pub fn move_to_prev_monitor(window: Window) -> Result<(), String> {
     Ok(())
 }
 
+/// 名前を指定したモニターへウィンドウを移動 (center: true で中央に配置、既定は左上)
 #[tauri::command]
-pub async fn simulate_mouse_move(window: Window, x: f64, y: f64, monitor_name: Option<String>) -> Result<(), String> {
-    desktop::simulate_mouse_move(window, x, y, monitor_name).await
+pub fn move_to_monitor(window: Window, monitor_name: String, center: Option<bool>) -> Result<(), String> {
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let monitor = monitors.iter()
+        .find(|m| m.name().map(|s| s.as_str()) == Some(monitor_name.as_str()))
+        .ok_or_else(|| format!("Monitor not found: {}", monitor_name))?;
+
+    let origin = monitor.position();
+    let position = if center.unwrap_or(false) {
+        let monitor_size = monitor.size();
+        let window_size = window.outer_size().map_err(|e| e.to_string())?;
+        PhysicalPosition::new(
+            origin.x + (monitor_size.width as i32 - window_size.width as i32).max(0) / 2,
+            origin.y + (monitor_size.height as i32 - window_size.height as i32).max(0) / 2,
+        )
+    } else {
+        *origin
+    };
+
+    window.set_position(position).map_err(|e| e.to_string())
 }
 
+/// 現在のモニターの半分/4分の1にウィンドウをスナップ
+/// region: "left" | "right" | "top" | "bottom" | "top-left" | "top-right" | "bottom-left" | "bottom-right" | "full"
 #[tauri::command]
-pub fn simulate_click(button: String) {
-    desktop::simulate_click(button)
+pub fn snap_window(window: Window, region: String) -> Result<(), String> {
+    let monitor = window.current_monitor().map_err(|e| e.to_string())?
+        .ok_or("Current monitor not found")?;
+    let origin = monitor.position();
+    let size = monitor.size();
+    let (half_w, half_h) = (size.width / 2, size.height / 2);
+
+    // (x オフセット, y オフセット, 幅, 高さ) — モニター原点からの相対値
+    let (dx, dy, w, h) = match region.as_str() {
+        "left" => (0, 0, half_w, size.height),
+        "right" => (half_w, 0, size.width - half_w, size.height),
+        "top" => (0, 0, size.width, half_h),
+        "bottom" => (0, half_h, size.width, size.height - half_h),
+        "top-left" => (0, 0, half_w, half_h),
+        "top-right" => (half_w, 0, size.width - half_w, half_h),
+        "bottom-left" => (0, half_h, half_w, size.height - half_h),
+        "bottom-right" => (half_w, half_h, size.width - half_w, size.height - half_h),
+        "full" => (0, 0, size.width, size.height),
+        _ => return Err(format!("Unknown snap region: {}", region)),
+    };
+
+    // 最大化中は位置/サイズ変更が効かないため解除する
+    if window.is_maximized().unwrap_or(false) {
+        window.unmaximize().map_err(|e| e.to_string())?;
+    }
+
+    window.set_position(PhysicalPosition::new(origin.x + dx as i32, origin.y + dy as i32))
+        .map_err(|e| e.to_string())?;
+    window.set_size(PhysicalSize::new(w, h))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// リモート操作 (マウス/キーボード入力の注入) を許可するか切り替え。既定は無効
+#[tauri::command]
+pub fn set_remote_control_enabled(enabled: bool) {
+    desktop::set_remote_control_enabled(enabled)
+}
+
+#[tauri::command]
+pub fn is_remote_control_enabled() -> bool {
+    desktop::is_remote_control_enabled()
+}
+
+#[tauri::command]
+pub async fn simulate_mouse_move(window: Window, x: f64, y: f64, monitor_name: Option<String>, input: State<'_, InputQueue>) -> Result<(), String> {
+    desktop::simulate_mouse_move(window, x, y, monitor_name, &input).await
+}
+
+#[tauri::command]
+pub async fn simulate_mouse_move_relative(dx: i32, dy: i32, input: State<'_, InputQueue>) -> Result<(), String> {
+    desktop::simulate_mouse_move_relative(dx, dy, &input).await
+}
+
+#[tauri::command]
+pub async fn simulate_click(button: String, count: Option<u32>, input: State<'_, InputQueue>) -> Result<(), String> {
+    desktop::simulate_click(button, count.unwrap_or(1), &input).await
 }
 
 #[tauri::command]
-pub fn simulate_scroll(delta_x: i32, delta_y: i32) {
-    desktop::simulate_scroll(delta_x, delta_y)
+pub async fn simulate_scroll(delta_x: i32, delta_y: i32, input: State<'_, InputQueue>) -> Result<(), String> {
+    desktop::simulate_scroll(delta_x, delta_y, &input).await
 }
 
 #[tauri::command]
-pub fn simulate_key(key: String) {
-    desktop::simulate_key(key)
+pub async fn simulate_key(key: String, input: State<'_, InputQueue>) -> Result<(), String> {
+    desktop::simulate_key(key, &input).await
 }
 
 #[tauri::command]
@@ -21,6 +21,72 @@ fn get_app_info() -> serde_json::Value {
     })
 }
 
+/// 各サブシステムの状態 (不具合報告用の診断情報)
+#[derive(serde::Serialize)]
+struct AppStatus {
+    client_initialized: bool,
+    gateway_connected: bool,
+    gateway_session_id: Option<String>,
+    guilds_in_store: usize,
+    db_path: String,
+    db_size_bytes: u64,
+}
+
+/// 診断用ステータスを取得するコマンド
+#[tauri::command]
+fn get_status(
+    discord_state: tauri::State<'_, services::state::DiscordState>,
+    gateway_sender: tauri::State<'_, bridge::gateway::GatewaySender>,
+    session_state: tauri::State<'_, bridge::gateway::SessionState>,
+    guild_state: tauri::State<'_, services::guild_state::GuildStateHandle>,
+    db_state: tauri::State<'_, store::DatabaseState>,
+) -> Result<AppStatus, String> {
+    let client_initialized = discord_state.client.lock().map_err(|e| e.to_string())?.is_some();
+    let gateway_connected = gateway_sender.0.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|tx| !tx.is_closed())
+        .unwrap_or(false);
+    let gateway_session_id = session_state.0.lock().map_err(|e| e.to_string())?.clone();
+    let guilds_in_store = guild_state.lock().map_err(|e| e.to_string())?.members.len();
+    let db_size_bytes = std::fs::metadata(&db_state.db_path).map(|m| m.len()).unwrap_or(0);
+
+    Ok(AppStatus {
+        client_initialized,
+        gateway_connected,
+        gateway_session_id,
+        guilds_in_store,
+        db_path: db_state.db_path.to_string_lossy().to_string(),
+        db_size_bytes,
+    })
+}
+
+/// データの保存場所 (バックアップ・削除・不具合報告用)
+#[derive(serde::Serialize)]
+struct DataPaths {
+    app_data_dir: String,
+    db_path: String,
+    attachment_cache_dir: String,
+    avatar_cache_dir: String,
+}
+
+/// アプリデータ・DB・添付ファイルキャッシュのパスを取得するコマンド
+#[tauri::command]
+fn get_data_paths(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, store::DatabaseState>,
+) -> Result<DataPaths, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let attachment_cache_dir = store::attachments::attachment_cache_dir(&app)?;
+    let avatar_cache_dir = store::avatars::avatar_cache_dir(&app)?;
+
+    Ok(DataPaths {
+        app_data_dir: app_data_dir.to_string_lossy().to_string(),
+        db_path: db_state.db_path.to_string_lossy().to_string(),
+        attachment_cache_dir: attachment_cache_dir.to_string_lossy().to_string(),
+        avatar_cache_dir: avatar_cache_dir.to_string_lossy().to_string(),
+    })
+}
+
 /// Tauriアプリケーションを実行
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -35,13 +101,20 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(tauri::generate_handler![
             get_app_info,
+            get_status,
+            get_data_paths,
             // Bridge: System/Desktop
             bridge::system::get_monitors,
             bridge::system::get_window_position,
             bridge::system::set_window_position,
             bridge::system::move_to_next_monitor,
             bridge::system::move_to_prev_monitor,
+            bridge::system::move_to_monitor,
+            bridge::system::snap_window,
+            bridge::system::set_remote_control_enabled,
+            bridge::system::is_remote_control_enabled,
             bridge::system::simulate_mouse_move,
+            bridge::system::simulate_mouse_move_relative,
             bridge::system::simulate_click,
             bridge::system::simulate_scroll,
             bridge::system::simulate_key,
@@ -49,32 +122,82 @@ pub fn run() {
             // Bridge: Capture
             bridge::capture::get_capture_sources,
             bridge::capture::get_source_frame,
+            bridge::capture::get_window_frame_by_title,
+            bridge::capture::set_capture_min_interval,
             // Bridge: Identity
             bridge::identity::init_client,
+            bridge::identity::validate_token,
+            bridge::identity::set_api_base,
+            bridge::identity::get_identify_config,
+            bridge::identity::set_identify_config,
             // Bridge: Social (Discord)
             bridge::social::get_guilds,
+            bridge::social::get_guild,
+            bridge::social::leave_guild,
+            bridge::social::sync_channel_muted,
             bridge::social::get_roles,
             bridge::social::get_members,
+            bridge::social::get_members_merged,
+            bridge::social::kick_member,
+            bridge::social::ban_member,
+            bridge::social::set_nickname,
+            bridge::social::add_member_role,
+            bridge::social::remove_member_role,
+            bridge::social::get_invites,
+            bridge::social::create_invite,
+            bridge::social::get_audit_log,
             bridge::social::get_channels,
             bridge::social::get_messages,
+            bridge::social::get_messages_around,
+            bridge::social::fetch_new_messages,
             bridge::social::send_message,
+            bridge::social::send_message_with_embed,
             bridge::social::delete_message,
+            bridge::social::ack_message,
+            bridge::social::get_reaction_users,
+            bridge::social::remove_all_reactions,
+            bridge::social::remove_reaction_emoji,
             bridge::social::fetch_all_history,
+            bridge::social::cancel_history_fetch,
             bridge::social::search_discord_api,
+            bridge::social::hybrid_search,
             bridge::social::get_archived_threads,
             bridge::social::get_forum_active_threads,
+            bridge::social::create_thread,
+            bridge::social::join_thread,
+            bridge::social::leave_thread,
+            bridge::social::get_thread_members,
             bridge::social::get_guild_members_from_store,
+            bridge::social::get_guild_members_by_status,
+            bridge::social::clear_guild_members,
+            bridge::social::get_channels_from_store,
+            bridge::social::get_roles_from_store,
+            bridge::social::get_guild_notification_settings,
+            bridge::social::resolve_mentions,
             bridge::social::get_voice_states,
+            bridge::social::get_voice_channel_participants,
             bridge::social::get_application_commands,
             bridge::social::get_application_commands,
             bridge::social::send_interaction,
+            bridge::social::send_autocomplete_interaction,
+            bridge::social::send_component_interaction,
+            bridge::social::send_modal_submit,
             bridge::social::get_dms,
             bridge::social::get_current_user,
+            bridge::social::get_user_settings,
+            bridge::social::get_voice_regions,
             
             // Gateway (moved to bridge as it is a controller)
             bridge::gateway::start_gateway,
             bridge::gateway::update_status,
+            bridge::gateway::set_afk_timeout,
+            bridge::gateway::user_active,
+            bridge::gateway::set_active_channel,
+            bridge::gateway::set_voice_channel,
+            bridge::gateway::set_self_mute_deaf,
             bridge::gateway::subscribe_member_list,
+            bridge::gateway::update_member_list_range,
+            bridge::gateway::request_guild_members,
             
             // Bridge: Room (Unified)
             bridge::room::fetch_messages,
@@ -82,17 +205,39 @@ pub fn run() {
 
             // Store (Database) commands
             store::get_cached_messages,
-            store::search_messages
+            store::search_messages,
+            store::export_channel,
+            store::get_cache_stats,
+            store::prune_messages,
+            store::check_db_integrity,
+            store::read_states::set_read_state,
+            store::read_states::get_read_states,
+            store::channel_settings::set_channel_muted,
+            store::channel_settings::get_channel_settings,
+            store::attachments::download_attachment,
+            store::attachments::clear_attachment_cache,
+            store::avatars::get_avatar,
+            store::avatars::get_guild_icon,
+            store::avatars::clear_avatar_cache
         ])
         .setup(|app| {
             // Discord状態の初期化
             app.manage(services::state::DiscordState::new());
+            // トークン失効 (REST の 401) 時にクライアントを破棄して再ログインを促す
+            bridge::identity::init_auth_expiry_watch(app.handle());
 
 
 
             // Database状態の初期化
             let app_data_dir = app.path().app_data_dir().expect("Failed to get app data dir");
-            let db_state = store::DatabaseState::new(app_data_dir).expect("Failed to initialize database");
+            // 開けない場合もアプリは起動させ、メモリ上のDBで動かす (キャッシュは保存されない)
+            let db_state = match store::DatabaseState::new(app_data_dir.clone()) {
+                Ok(db_state) => db_state,
+                Err(e) => {
+                    eprintln!("[Store] Failed to initialize database, falling back to in-memory database: {}", e);
+                    store::DatabaseState::in_memory(app_data_dir.join("messages.db"))?
+                }
+            };
             app.manage(db_state);
 
             // Gateway状態の初期化
@@ -103,6 +248,32 @@ pub fn run() {
             let session_state = bridge::gateway::SessionState(Arc::new(Mutex::new(None)));
             app.manage(session_state);
 
+            // Gateway停止フラグの初期化
+            app.manage(bridge::gateway::GatewayShutdown::default());
+
+            // 自動退席 (AFK) 監視の初期化 (set_afk_timeout で有効化されるまで何もしない)
+            app.manage(bridge::gateway::AfkState::default());
+            bridge::gateway::init_afk_watch(app.handle());
+
+            // Discordボイスチャンネル状態の初期化
+            let voice_channel_state = bridge::gateway::VoiceChannelState(Arc::new(Mutex::new(Default::default())));
+            app.manage(voice_channel_state);
+
+            // 表示中チャンネル (再接続時の取りこぼし補完用) の初期化
+            app.manage(bridge::gateway::ActiveChannelState::default());
+
+            // 送信済みメッセージID (Gatewayエコー判定用) の初期化
+            app.manage(bridge::gateway::RecentSentMessages::default());
+
+            // 入力中ユーザー (typing_update 用) の初期化
+            app.manage(bridge::gateway::TypingState::default());
+
+            // 全履歴取得のキャンセルフラグの初期化
+            app.manage(bridge::social::HistoryFetchCancel::default());
+
+            // フレーム取得スロットリング状態の初期化
+            app.manage(bridge::capture::FrameThrottleState(Arc::new(Mutex::new(Default::default()))));
+
             // Guild Member/Presence状態の初期化
             let guild_state = services::guild_state::create_guild_state();
             app.manage(guild_state);
@@ -116,6 +287,12 @@ pub fn run() {
             // クリップボード監視開始 (Logic is in services/desktop)
             services::desktop::init_clipboard(app.handle(), clipboard_state);
 
+            // 入力ワーカーの起動 (リモート操作の入力を1つのEnigoで順番に適用)
+            app.manage(services::desktop::InputQueue::start());
+
+            // モニター構成の変化監視 (ホットプラグ対応)
+            services::desktop::init_monitor_watch(app.handle());
+
             // 開発時にDevToolsを開く
             #[cfg(debug_assertions)]
             {
@@ -126,8 +303,17 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("Tauriアプリケーションの起動に失敗しました");
+        .build(tauri::generate_context!())
+        .expect("Tauriアプリケーションの起動に失敗しました")
+        .run(|app, event| {
+            // 終了時にGatewayの再接続ループを止め、DBをチェックポイントしてから終了する
+            if let tauri::RunEvent::Exit = event {
+                bridge::gateway::shutdown_gateway(app);
+                if let Some(db) = app.try_state::<store::DatabaseState>() {
+                    db.shutdown();
+                }
+            }
+        });
 }
 
 
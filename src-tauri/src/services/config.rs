@@ -0,0 +1,89 @@
+// 実行時設定
+// Discord APIのベースURLは環境変数 DISCORD_API_BASE または set_api_base で上書き可能 (モックサーバー/プロキシ用)
+// Gateway Identify のプロパティ/capabilities は環境変数 DISCORD_GATEWAY_CAPABILITIES または set_identify_config で上書き可能
+
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+pub const DEFAULT_API_BASE: &str = "https://discord.com/api/v10";
+pub const CDN_BASE: &str = "https://cdn.discordapp.com";
+/// Identify の capabilities 既定値 (Discordクライアントの仕様変更に合わせて更新する)
+pub const DEFAULT_GATEWAY_CAPABILITIES: u64 = 16381;
+
+fn api_base_lock() -> &'static RwLock<String> {
+    static API_BASE: OnceLock<RwLock<String>> = OnceLock::new();
+    API_BASE.get_or_init(|| {
+        let base = std::env::var("DISCORD_API_BASE")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| DEFAULT_API_BASE.to_string());
+        RwLock::new(base.trim_end_matches('/').to_string())
+    })
+}
+
+/// 現在のAPIベースURLを取得
+pub fn api_base() -> String {
+    api_base_lock().read().map(|s| s.clone()).unwrap_or_else(|_| DEFAULT_API_BASE.to_string())
+}
+
+/// APIベースURLを上書き
+pub fn set_api_base(base: String) {
+    if let Ok(mut lock) = api_base_lock().write() {
+        *lock = base.trim_end_matches('/').to_string();
+    }
+}
+
+/// CDNアセット (avatars / icons 等) のURLを生成
+/// ハッシュが "a_" で始まるものはアニメーションなので .gif を使う
+pub fn cdn_asset_url(base: &str, id: &str, hash: &str) -> String {
+    let ext = if hash.starts_with("a_") { "gif" } else { "png" };
+    format!("{}/{}/{}/{}.{}", CDN_BASE, base, id, hash, ext)
+}
+
+/// Gateway Identify (OP 2) で送るクライアント情報
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IdentifyConfig {
+    pub os: String,
+    pub browser: String,
+    pub device: String,
+    pub capabilities: u64,
+}
+
+impl Default for IdentifyConfig {
+    fn default() -> Self {
+        // Discordクライアントと同じ表記に合わせる
+        let os = match std::env::consts::OS {
+            "windows" => "Windows",
+            "macos" => "Mac OS X",
+            "linux" => "Linux",
+            other => other,
+        };
+        let capabilities = std::env::var("DISCORD_GATEWAY_CAPABILITIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_GATEWAY_CAPABILITIES);
+        Self {
+            os: os.to_string(),
+            browser: "p2d".to_string(),
+            device: "p2d".to_string(),
+            capabilities,
+        }
+    }
+}
+
+fn identify_config_lock() -> &'static RwLock<IdentifyConfig> {
+    static IDENTIFY_CONFIG: OnceLock<RwLock<IdentifyConfig>> = OnceLock::new();
+    IDENTIFY_CONFIG.get_or_init(|| RwLock::new(IdentifyConfig::default()))
+}
+
+/// 現在のIdentify設定を取得
+pub fn identify_config() -> IdentifyConfig {
+    identify_config_lock().read().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Identify設定を上書き (次回のGateway接続から反映)
+pub fn set_identify_config(config: IdentifyConfig) {
+    if let Ok(mut lock) = identify_config_lock().write() {
+        *lock = config;
+    }
+}
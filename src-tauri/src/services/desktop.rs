@@ -1,5 +1,6 @@
 use tauri::{State, Window, Emitter};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::Duration;
 use arboard::Clipboard;
@@ -9,13 +10,146 @@ use enigo::{
     Button, 
     Direction, 
     Axis,
-    Coordinate
+    Coordinate,
+    InputError
 };
 
 // 無限ループ防止のためのクリップボード状態
 pub struct ClipboardState(pub Arc<Mutex<String>>);
 
-#[derive(serde::Serialize)]
+/// 入力ワーカーに送る操作
+pub enum InputEvent {
+    MoveAbs { x: i32, y: i32 },
+    MoveRel { dx: i32, dy: i32 },
+    Click { button: Button, count: u32 },
+    Scroll { delta_x: i32, delta_y: i32 },
+    Text(String),
+}
+
+/// 入力の適用結果を呼び出し元に返すチャネル
+type InputReply = tokio::sync::oneshot::Sender<Result<(), String>>;
+
+/// 入力ワーカーへのキュー
+/// 高頻度のリモート操作でも順序を保ち、1つのEnigoで順番に適用する
+pub struct InputQueue(pub Mutex<mpsc::Sender<(InputEvent, InputReply)>>);
+
+impl InputQueue {
+    /// 入力ワーカースレッドを起動してキューを返す
+    pub fn start() -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || run_input_worker(rx));
+        Self(Mutex::new(tx))
+    }
+
+    /// 操作をキューに積み、ワーカーが適用し終えるまで待って結果を返す
+    async fn send(&self, event: InputEvent) -> Result<(), String> {
+        const UNSUPPORTED: &str = "Input simulation is not supported in this environment (e.g. Wayland session or no display)";
+        let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+        {
+            let tx = self.0.lock().map_err(|e| e.to_string())?;
+            // Enigoを生成できずワーカーが終了している場合は送信に失敗する
+            tx.send((event, reply_tx)).map_err(|_| UNSUPPORTED.to_string())?;
+        }
+        reply_rx.await.map_err(|_| UNSUPPORTED.to_string())?
+    }
+}
+
+fn run_input_worker(rx: mpsc::Receiver<(InputEvent, InputReply)>) {
+    let mut enigo = match Enigo::new(&Settings::default()) {
+        Ok(enigo) => enigo,
+        Err(e) => {
+            eprintln!("Input simulation unavailable: {}", e);
+            return;
+        }
+    };
+
+    let mut pending: Option<(InputEvent, InputReply)> = None;
+    loop {
+        let (event, reply) = match pending.take() {
+            Some(item) => item,
+            None => match rx.recv() {
+                Ok(item) => item,
+                Err(_) => break,
+            },
+        };
+        let mut replies = vec![reply];
+
+        // キューに溜まった連続するマウス移動はまとめる (絶対移動は最新のみ、相対移動は合算)
+        // まとめた操作の呼び出し元にはすべて同じ結果を返す
+        let event = match event {
+            InputEvent::MoveAbs { .. } | InputEvent::MoveRel { .. } => {
+                let mut current = event;
+                while let Ok((next, next_reply)) = rx.try_recv() {
+                    current = match (current, next) {
+                        (InputEvent::MoveAbs { .. }, next @ InputEvent::MoveAbs { .. }) => next,
+                        (InputEvent::MoveRel { dx, dy }, InputEvent::MoveRel { dx: ndx, dy: ndy }) => {
+                            InputEvent::MoveRel { dx: dx.saturating_add(ndx), dy: dy.saturating_add(ndy) }
+                        }
+                        (prev, next) => {
+                            pending = Some((next, next_reply));
+                            current = prev;
+                            break;
+                        }
+                    };
+                    replies.push(next_reply);
+                }
+                current
+            }
+            other => other,
+        };
+
+        // キューに積まれている間にリモート操作が無効化された場合は適用しない
+        let result = if is_remote_control_enabled() {
+            apply_input(&mut enigo, event).map_err(|e| format!("Input simulation failed: {}", e))
+        } else {
+            Ok(())
+        };
+        for reply in replies {
+            // 呼び出し元が待たずに終了していても問題ない
+            let _ = reply.send(result.clone());
+        }
+    }
+}
+
+fn apply_input(enigo: &mut Enigo, event: InputEvent) -> Result<(), InputError> {
+    match event {
+        // move_mouse は Coordinate::Abs で絶対座標移動
+        InputEvent::MoveAbs { x, y } => enigo.move_mouse(x, y, Coordinate::Abs),
+        InputEvent::MoveRel { dx, dy } => enigo.move_mouse(dx, dy, Coordinate::Rel),
+        InputEvent::Click { button, count } => {
+            for _ in 0..count {
+                enigo.button(button, Direction::Click)?;
+            }
+            Ok(())
+        }
+        InputEvent::Scroll { delta_x, delta_y } => {
+            if delta_y != 0 {
+                enigo.scroll(delta_y, Axis::Vertical)?;
+            }
+            if delta_x != 0 {
+                enigo.scroll(delta_x, Axis::Horizontal)?;
+            }
+            Ok(())
+        }
+        // 簡易的な実装: 文字列をそのままタイプ
+        InputEvent::Text(text) => enigo.text(&text),
+    }
+}
+
+// リモート操作 (入力注入) の許可フラグ。既定は無効で、ユーザーが明示的に有効化した場合のみ入力を受け付ける
+static REMOTE_CONTROL_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// リモート操作の有効/無効を切り替え
+pub fn set_remote_control_enabled(enabled: bool) {
+    REMOTE_CONTROL_ENABLED.store(enabled, Ordering::SeqCst);
+    println!("Remote control {}", if enabled { "enabled" } else { "disabled" });
+}
+
+pub fn is_remote_control_enabled() -> bool {
+    REMOTE_CONTROL_ENABLED.load(Ordering::SeqCst)
+}
+
+#[derive(serde::Serialize, Clone, PartialEq)]
 pub struct MonitorInfo {
     name: String,
     width: u32,
@@ -28,80 +162,134 @@ pub struct MonitorInfo {
 
 pub fn get_monitors(window: Window) -> Result<Vec<MonitorInfo>, String> {
     let monitors = window.available_monitors().map_err(|e| e.to_string())?;
-    let info = monitors.into_iter().map(|m| {
-        let size = m.size();
-        let pos = m.position();
-        MonitorInfo {
-            name: m.name().map(|n| n.to_string()).unwrap_or_else(|| "Unknown".to_string()),
-            width: size.width,
-            height: size.height,
-            x: pos.x,
-            y: pos.y,
+    Ok(monitors.iter().map(to_monitor_info).collect())
+}
+
+fn to_monitor_info(m: &tauri::Monitor) -> MonitorInfo {
+    let size = m.size();
+    let pos = m.position();
+    MonitorInfo {
+        name: m.name().map(|n| n.to_string()).unwrap_or_else(|| "Unknown".to_string()),
+        width: size.width,
+        height: size.height,
+        x: pos.x,
+        y: pos.y,
+    }
+}
+
+// モニター構成のキャッシュ (simulate_mouse_move のホットパスでOS問い合わせを避ける)
+// init_monitor_watch が構成の変化を検知したときに更新される
+static MONITOR_CACHE: Mutex<Option<Vec<MonitorInfo>>> = Mutex::new(None);
+
+/// キャッシュから名前でモニターを探す (キャッシュが空なら1度だけOSに問い合わせる)
+fn find_cached_monitor(window: &Window, name: &str) -> Result<Option<MonitorInfo>, String> {
+    let mut cache = MONITOR_CACHE.lock().map_err(|e| e.to_string())?;
+    if cache.is_none() {
+        let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+        *cache = Some(monitors.iter().map(to_monitor_info).collect());
+    }
+    Ok(cache.as_ref().and_then(|monitors| monitors.iter().find(|m| m.name == name).cloned()))
+}
+
+/// モニターの接続/切断・解像度/配置変更を監視し、変化があれば monitors-changed を発行
+pub fn init_monitor_watch(app: &tauri::AppHandle) {
+    let app_handle = app.clone();
+    thread::spawn(move || {
+        let mut last: Option<Vec<MonitorInfo>> = None;
+        loop {
+            if let Ok(monitors) = app_handle.available_monitors() {
+                let current: Vec<MonitorInfo> = monitors.iter().map(to_monitor_info).collect();
+                // 初回は基準として保持するだけ
+                if last.as_ref().is_some_and(|prev| *prev != current) {
+                    println!("Monitor configuration changed: {} monitor(s)", current.len());
+                    if let Ok(mut cache) = MONITOR_CACHE.lock() {
+                        *cache = Some(current.clone());
+                    }
+                    if let Err(e) = app_handle.emit("monitors-changed", &current) {
+                        eprintln!("Failed to emit event: {}", e);
+                    }
+                }
+                last = Some(current);
+            }
+            thread::sleep(Duration::from_millis(2000));
         }
-    }).collect();
-    Ok(info)
+    });
 }
 
 /// マウス移動をシミュレート (0.0 - 1.0 の正規化座標)
 
-pub async fn simulate_mouse_move(window: Window, x: f64, y: f64, monitor_name: Option<String>) -> Result<(), String> {
-    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
-    
+pub async fn simulate_mouse_move(window: Window, x: f64, y: f64, monitor_name: Option<String>, input: &InputQueue) -> Result<(), String> {
+    if !is_remote_control_enabled() {
+        return Ok(());
+    }
+    // モニター名指定時はキャッシュを引くだけ (毎回の available_monitors 呼び出しを避ける)
     let target_monitor = if let Some(name) = monitor_name {
-        monitors.into_iter().find(|m| m.name().as_deref() == Some(&name))
+        find_cached_monitor(&window, &name)?
     } else {
-        window.current_monitor().map_err(|e| e.to_string())?
+        window.current_monitor().map_err(|e| e.to_string())?.as_ref().map(to_monitor_info)
     };
 
     if let Some(monitor) = target_monitor {
-        let size = monitor.size();
-        let position = monitor.position(); // モニターの左上座標を取得
-        
-        let width = size.width as f64;
-        let height = size.height as f64;
+        let width = monitor.width as f64;
+        let height = monitor.height as f64;
         
         // モニターのオフセットを加算して正しい絶対座標を計算
-        let target_x = position.x + (x * width) as i32;
-        let target_y = position.y + (y * height) as i32;
+        let target_x = monitor.x + (x * width) as i32;
+        let target_y = monitor.y + (y * height) as i32;
         
-        let mut enigo = Enigo::new(&Settings::default()).unwrap();
-        // move_mouse は Coordinate::Abs で絶対座標移動
-        let _ = enigo.move_mouse(target_x, target_y, Coordinate::Abs);
+        input.send(InputEvent::MoveAbs { x: target_x, y: target_y }).await
+    } else {
+        Err("Target monitor not found".to_string())
     }
-    Ok(())
 }
 
-/// マウスクリックをシミュレート
+/// マウスを現在位置から相対移動 (ドラッグやゲーム向けのリモート操作用)
+
+pub async fn simulate_mouse_move_relative(dx: i32, dy: i32, input: &InputQueue) -> Result<(), String> {
+    if !is_remote_control_enabled() {
+        return Ok(());
+    }
+    if dx == 0 && dy == 0 {
+        return Ok(());
+    }
+    input.send(InputEvent::MoveRel { dx, dy }).await
+}
+
+/// マウスクリックをシミュレート (count: 2 でダブルクリック、最大3)
+/// IPCを往復せずに連続でクリックするので、OSにダブルクリックとして認識される
 
-pub fn simulate_click(button: String) {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
+pub async fn simulate_click(button: String, count: u32, input: &InputQueue) -> Result<(), String> {
+    if !is_remote_control_enabled() {
+        return Ok(());
+    }
     let btn = match button.as_str() {
         "left" => Button::Left,
         "right" => Button::Right,
         "middle" => Button::Middle,
-        _ => return,
+        _ => return Err(format!("Unknown mouse button: {}", button)),
     };
-    let _ = enigo.button(btn, Direction::Click);
+    input.send(InputEvent::Click { button: btn, count: count.clamp(1, 3) }).await
 }
 
 /// スクロールをシミュレート
 
-pub fn simulate_scroll(delta_x: i32, delta_y: i32) {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
-    if delta_y != 0 {
-        let _ = enigo.scroll(delta_y, Axis::Vertical);
+pub async fn simulate_scroll(delta_x: i32, delta_y: i32, input: &InputQueue) -> Result<(), String> {
+    if !is_remote_control_enabled() {
+        return Ok(());
     }
-    if delta_x != 0 {
-        let _ = enigo.scroll(delta_x, Axis::Horizontal);
+    if delta_x == 0 && delta_y == 0 {
+        return Ok(());
     }
+    input.send(InputEvent::Scroll { delta_x, delta_y }).await
 }
 
 /// キー入力をシミュレート
 
-pub fn simulate_key(key: String) {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
-    // 簡易的な実装: 文字列をそのままタイプ
-    let _ = enigo.text(&key);
+pub async fn simulate_key(key: String, input: &InputQueue) -> Result<(), String> {
+    if !is_remote_control_enabled() {
+        return Ok(());
+    }
+    input.send(InputEvent::Text(key)).await
 }
 
 /// クリップボード書き込みコマンド
@@ -0,0 +1,109 @@
+// 共通エラー型
+// フロントエンドが「トークン切れ」「レート制限」などを区別できるよう、種別付きでJSONとして返す
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Unauthorized, // 401: トークン無効/期限切れ
+    RateLimited,  // 429
+    Slowmode,     // 429 (code 20016): チャンネルの低速モード
+    Forbidden,    // 403: 権限不足
+    NotFound,     // 404
+    Network,      // 接続失敗・タイムアウト等
+    Other,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub status: Option<u16>,
+    /// 再試行までの秒数 (Slowmode / RateLimited のみ)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<f64>,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into(), status: None, retry_after: None }
+    }
+
+    /// 権限不足 (403) を分かりやすいメッセージで返す
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self { kind: ErrorKind::Forbidden, message: message.into(), status: Some(403), retry_after: None }
+    }
+
+    /// リクエスト送信自体の失敗
+    pub fn network(e: reqwest::Error) -> Self {
+        Self::new(ErrorKind::Network, format!("Request failed: {}", e))
+    }
+
+    /// HTTPステータスからエラー種別を判定
+    pub fn from_status(status: reqwest::StatusCode, body: String) -> Self {
+        if status.as_u16() == 429 {
+            return Self::from_rate_limit(body);
+        }
+        let kind = match status.as_u16() {
+            401 => ErrorKind::Unauthorized,
+            403 => ErrorKind::Forbidden,
+            404 => ErrorKind::NotFound,
+            _ => ErrorKind::Other,
+        };
+        Self {
+            kind,
+            message: format!("API Error: Status {} - {}", status, body),
+            status: Some(status.as_u16()),
+            retry_after: None,
+        }
+    }
+
+    /// 429 のボディ ({ "retry_after": 秒, "code": ... }) を読み取る
+    /// code 20016 は低速モードなので、残り秒数付きの Slowmode として返す
+    fn from_rate_limit(body: String) -> Self {
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        let retry_after = json["retry_after"].as_f64();
+        if json["code"].as_u64() == Some(20016) {
+            let seconds = retry_after.unwrap_or(0.0);
+            return Self {
+                kind: ErrorKind::Slowmode,
+                message: format!("Slowmode is enabled. Try again in {:.0} seconds.", seconds.ceil()),
+                status: Some(429),
+                retry_after: Some(seconds),
+            };
+        }
+        Self {
+            kind: ErrorKind::RateLimited,
+            message: format!("API Error: Status 429 Too Many Requests - {}", body),
+            status: Some(429),
+            retry_after,
+        }
+    }
+
+    /// 失敗レスポンスを読み取ってエラーに変換
+    pub async fn from_response(res: reqwest::Response) -> Self {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        Self::from_status(status, body)
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+}
@@ -4,7 +4,7 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use crate::services::models::{
-    DiscordUser, Activity, ClientStatus, MemberWithPresence, VoiceState
+    DiscordUser, Activity, ClientStatus, MemberWithPresence, VoiceState, VoiceParticipant, SimpleChannel, SimpleRole, GuildNotificationSettings, SimpleMember
 };
 
 /// ギルドごとのメンバー・プレゼンス・ボイス状態を管理
@@ -14,6 +14,14 @@ pub struct GuildMemberStore {
     pub members: HashMap<String, HashMap<String, MemberWithPresence>>,
     // guild_id -> { user_id -> VoiceState }
     pub voice_states: HashMap<String, HashMap<String, VoiceState>>,
+    // guild_id -> 受信済みGUILD_MEMBERS_CHUNK数 (OP 8 リクエスト中のみ)
+    pub member_chunks_received: HashMap<String, u32>,
+    // guild_id -> チャンネル一覧 (GUILD_CREATE で受信, Discord順)
+    pub channels: HashMap<String, Vec<SimpleChannel>>,
+    // guild_id -> ロール一覧 (GUILD_CREATE で受信)
+    pub roles: HashMap<String, Vec<SimpleRole>>,
+    // guild_id -> 通知設定 (READY の user_guild_settings で受信, DM は "@me")
+    pub notification_settings: HashMap<String, GuildNotificationSettings>,
 }
 
 impl GuildMemberStore {
@@ -57,9 +65,11 @@ impl GuildMemberStore {
         let guild_members = self.members.entry(guild_id.to_string()).or_insert_with(HashMap::new);
         if !guild_members.contains_key(&user.id) {
             guild_members.insert(user.id.clone(), MemberWithPresence {
+                avatar_url: user_avatar_url(&user),
                 user,
                 roles: vec![],
                 nick: None,
+                guild_avatar: None,
                 joined_at: String::new(),
                 status,
                 activities,
@@ -75,6 +85,35 @@ impl GuildMemberStore {
         }
     }
 
+    /// メンバーのニックネームを更新（メンバーが存在する場合）
+    pub fn update_member_nick(&mut self, guild_id: &str, user_id: &str, nick: Option<String>) {
+        if let Some(member) = self.members.get_mut(guild_id).and_then(|m| m.get_mut(user_id)) {
+            member.nick = nick;
+        }
+    }
+
+    /// メンバーのロールを付与/剥奪（メンバーが存在する場合）
+    pub fn set_member_role(&mut self, guild_id: &str, user_id: &str, role_id: &str, has_role: bool) {
+        if let Some(member) = self.members.get_mut(guild_id).and_then(|m| m.get_mut(user_id)) {
+            member.roles.retain(|r| r != role_id);
+            if has_role {
+                member.roles.push(role_id.to_string());
+            }
+        }
+    }
+
+    /// GUILD_MEMBERS_CHUNK の受信を記録し、全チャンクが揃ったら true を返す
+    pub fn record_member_chunk(&mut self, guild_id: &str, chunk_count: u32) -> bool {
+        let received = self.member_chunks_received.entry(guild_id.to_string()).or_insert(0);
+        *received += 1;
+        if *received >= chunk_count {
+            self.member_chunks_received.remove(guild_id);
+            true
+        } else {
+            false
+        }
+    }
+
     /// ボイス状態を更新
     pub fn update_voice_state(&mut self, guild_id: &str, voice_state: VoiceState) {
         let guild_voice = self.voice_states.entry(guild_id.to_string()).or_insert_with(HashMap::new);
@@ -87,11 +126,44 @@ impl GuildMemberStore {
         }
     }
 
-    /// ギルドのメンバー一覧を取得
+    /// ギルドのメンバー一覧を取得 (オンライン → 退席中 → 取り込み中 → オフライン、同順位は表示名順)
     pub fn get_members(&self, guild_id: &str) -> Vec<MemberWithPresence> {
-        self.members.get(guild_id)
+        let mut members: Vec<MemberWithPresence> = self.members.get(guild_id)
             .map(|m| m.values().cloned().collect())
-            .unwrap_or_default()
+            .unwrap_or_default();
+        sort_members(&mut members);
+        members
+    }
+
+    /// REST で取得したメンバー一覧にストアのプレゼンスを重ねる
+    /// 名簿・ロール・ニックネームは REST を正とし、Gateway で未受信のメンバーは offline 扱い
+    pub fn merge_members(&self, guild_id: &str, rest_members: Vec<SimpleMember>) -> Vec<MemberWithPresence> {
+        let stored = self.members.get(guild_id);
+        let mut members: Vec<MemberWithPresence> = rest_members.into_iter().map(|m| {
+            let presence = stored.and_then(|s| s.get(&m.user.id));
+            MemberWithPresence {
+                guild_avatar: presence.and_then(|p| p.guild_avatar.clone()),
+                avatar_url: user_avatar_url(&m.user),
+                status: presence.map(|p| p.status.clone()).unwrap_or_else(|| "offline".to_string()),
+                activities: presence.map(|p| p.activities.clone()).unwrap_or_default(),
+                client_status: presence.map(|p| p.client_status.clone()).unwrap_or_default(),
+                user: m.user,
+                roles: m.roles,
+                nick: m.nick,
+                joined_at: m.joined_at,
+            }
+        }).collect();
+        sort_members(&mut members);
+        members
+    }
+
+    /// 指定ステータス (online / idle / dnd / offline) のメンバーのみ取得 (並び順は get_members と同じ)
+    pub fn get_members_by_status(&self, guild_id: &str, statuses: &[String]) -> Vec<MemberWithPresence> {
+        let mut members: Vec<MemberWithPresence> = self.members.get(guild_id)
+            .map(|m| m.values().filter(|member| statuses.contains(&member.status)).cloned().collect())
+            .unwrap_or_default();
+        sort_members(&mut members);
+        members
     }
 
     /// ギルドのボイス状態一覧を取得
@@ -101,13 +173,252 @@ impl GuildMemberStore {
             .unwrap_or_default()
     }
 
+    /// ボイスチャンネルの参加者一覧を取得 (メンバー情報と結合、表示名順)
+    pub fn get_voice_channel_participants(&self, guild_id: &str, channel_id: &str) -> Vec<VoiceParticipant> {
+        let members = self.members.get(guild_id);
+        let mut participants: Vec<VoiceParticipant> = self.voice_states.get(guild_id)
+            .map(|v| v.values()
+                .filter(|vs| vs.channel_id.as_deref() == Some(channel_id))
+                .map(|vs| {
+                    let member = members.and_then(|m| m.get(&vs.user_id));
+                    VoiceParticipant {
+                        user_id: vs.user_id.clone(),
+                        user: member.map(|m| m.user.clone()),
+                        nick: member.and_then(|m| m.nick.clone()),
+                        guild_avatar: member.and_then(|m| m.guild_avatar.clone()),
+                        self_mute: vs.self_mute,
+                        self_deaf: vs.self_deaf,
+                        mute: vs.mute,
+                        deaf: vs.deaf,
+                        self_stream: vs.self_stream,
+                        self_video: vs.self_video,
+                    }
+                })
+                .collect())
+            .unwrap_or_default();
+        participants.sort_by_cached_key(|p| {
+            p.nick.as_deref()
+                .or(p.user.as_ref().and_then(|u| u.global_name.as_deref()))
+                .or(p.user.as_ref().map(|u| u.username.as_str()))
+                .unwrap_or(&p.user_id)
+                .to_lowercase()
+        });
+        participants
+    }
+
+    /// ギルドのチャンネル一覧とロール一覧を置き換え
+    pub fn set_guild_metadata(&mut self, guild_id: &str, channels: Vec<SimpleChannel>, roles: Vec<SimpleRole>) {
+        self.channels.insert(guild_id.to_string(), channels);
+        self.roles.insert(guild_id.to_string(), roles);
+    }
+
+    /// ギルドのチャンネル一覧を取得 (未受信なら None)
+    pub fn get_channels(&self, guild_id: &str) -> Option<Vec<SimpleChannel>> {
+        self.channels.get(guild_id).cloned()
+    }
+
+    /// ギルドのロール一覧を取得 (未受信なら None)
+    pub fn get_roles(&self, guild_id: &str) -> Option<Vec<SimpleRole>> {
+        self.roles.get(guild_id).cloned()
+    }
+
+    /// ギルドのロール一覧を置き換え
+    pub fn set_roles(&mut self, guild_id: &str, roles: Vec<SimpleRole>) {
+        self.roles.insert(guild_id.to_string(), roles);
+    }
+
+    /// ロールを追加または更新 (GUILD_ROLE_CREATE / GUILD_ROLE_UPDATE)
+    /// ロール一覧が未受信のギルドは REST で取得されるまで何もしない
+    pub fn upsert_role(&mut self, guild_id: &str, role: SimpleRole) {
+        let Some(roles) = self.roles.get_mut(guild_id) else {
+            return;
+        };
+        match roles.iter_mut().find(|r| r.id == role.id) {
+            Some(existing) => *existing = role,
+            None => roles.push(role),
+        }
+    }
+
+    /// ロールを削除 (GUILD_ROLE_DELETE)。メンバーに付与されていた分も外す
+    pub fn remove_role(&mut self, guild_id: &str, role_id: &str) {
+        if let Some(roles) = self.roles.get_mut(guild_id) {
+            roles.retain(|r| r.id != role_id);
+        }
+        if let Some(members) = self.members.get_mut(guild_id) {
+            for member in members.values_mut() {
+                member.roles.retain(|r| r != role_id);
+            }
+        }
+    }
+
+    /// チャンネルを追加または更新 (CHANNEL_CREATE / CHANNEL_UPDATE / THREAD_CREATE / THREAD_UPDATE)
+    /// チャンネル一覧が未受信のギルドは REST で取得されるまで何もしない
+    pub fn upsert_channel(&mut self, guild_id: &str, channel: SimpleChannel) {
+        let Some(channels) = self.channels.get_mut(guild_id) else {
+            return;
+        };
+        match channels.iter_mut().find(|c| c.id == channel.id) {
+            Some(existing) => *existing = channel,
+            None => channels.push(channel),
+        }
+        sort_guild_channels(channels);
+    }
+
+    /// チャンネルを削除し、削除したIDを返す (CHANNEL_DELETE / THREAD_DELETE)
+    /// 親チャンネルが消えた場合はそのスレッドも消す
+    pub fn remove_channel(&mut self, guild_id: &str, channel_id: &str) -> Vec<String> {
+        let Some(channels) = self.channels.get_mut(guild_id) else {
+            return vec![];
+        };
+        let mut removed = vec![];
+        channels.retain(|c| {
+            let remove = c.id == channel_id || (is_thread(c) && c.parent_id.as_deref() == Some(channel_id));
+            if remove {
+                removed.push(c.id.clone());
+            }
+            !remove
+        });
+        removed
+    }
+
+    /// アクティブなスレッド一覧を同期 (THREAD_LIST_SYNC)
+    /// parent_ids が None ならギルド全体、指定があればその親チャンネルのスレッドだけを置き換える
+    pub fn sync_threads(&mut self, guild_id: &str, parent_ids: Option<&[String]>, threads: Vec<SimpleChannel>) {
+        let Some(channels) = self.channels.get_mut(guild_id) else {
+            return;
+        };
+        channels.retain(|c| {
+            !is_thread(c) || parent_ids.is_some_and(|ids| !c.parent_id.as_ref().is_some_and(|p| ids.contains(p)))
+        });
+        channels.extend(threads);
+        sort_guild_channels(channels);
+    }
+
+    /// 通知設定を一括で置き換え (READY)
+    pub fn set_notification_settings(&mut self, settings: Vec<GuildNotificationSettings>) {
+        self.notification_settings = settings.into_iter()
+            .map(|s| (s.guild_id.clone().unwrap_or_else(|| "@me".to_string()), s))
+            .collect();
+    }
+
+    /// ギルドの通知設定を取得 (未受信なら None)
+    pub fn get_notification_settings(&self, guild_id: &str) -> Option<GuildNotificationSettings> {
+        self.notification_settings.get(guild_id).cloned()
+    }
+
+    /// メッセージ本文のメンション (<@id> / <@!id> / <@&id> / <#id>) を名前に置き換える
+    /// ストアに無いIDのメンションは元の表記のまま残す
+    pub fn resolve_mentions(&self, guild_id: &str, content: &str) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut rest = content;
+
+        while let Some(start) = rest.find('<') {
+            result.push_str(&rest[..start]);
+            let candidate = &rest[start..];
+            let resolved = candidate.find('>')
+                .and_then(|end| Some((end, self.resolve_mention_token(guild_id, &candidate[1..end])?)));
+            match resolved {
+                Some((end, name)) => {
+                    result.push_str(&name);
+                    rest = &candidate[end + 1..];
+                }
+                None => {
+                    // メンションでなければ '<' だけ出力して続きから探す
+                    result.push('<');
+                    rest = &candidate[1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// メンション1つ分 (< > の中身) を名前に変換
+    fn resolve_mention_token(&self, guild_id: &str, inner: &str) -> Option<String> {
+        let is_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+
+        if let Some(role_id) = inner.strip_prefix("@&") {
+            if !is_id(role_id) {
+                return None;
+            }
+            let role = self.roles.get(guild_id)?.iter().find(|r| r.id == role_id)?;
+            return Some(format!("@{}", role.name));
+        }
+        if let Some(user_id) = inner.strip_prefix('@') {
+            let user_id = user_id.strip_prefix('!').unwrap_or(user_id);
+            if !is_id(user_id) {
+                return None;
+            }
+            let member = self.members.get(guild_id)?.get(user_id)?;
+            let name = member.nick.as_deref()
+                .or(member.user.global_name.as_deref())
+                .unwrap_or(&member.user.username);
+            return Some(format!("@{}", name));
+        }
+        if let Some(channel_id) = inner.strip_prefix('#') {
+            if !is_id(channel_id) {
+                return None;
+            }
+            let channel = self.channels.get(guild_id)?.iter().find(|c| c.id == channel_id)?;
+            return Some(format!("#{}", channel.name));
+        }
+        None
+    }
+
+    /// ギルドのメンバー情報のみをクリア (ボイス状態・チャンネル等は保持)
+    pub fn clear_members(&mut self, guild_id: &str) {
+        self.members.remove(guild_id);
+        self.member_chunks_received.remove(guild_id);
+    }
+
     /// ギルドをクリア
     pub fn clear_guild(&mut self, guild_id: &str) {
         self.members.remove(guild_id);
         self.voice_states.remove(guild_id);
+        self.member_chunks_received.remove(guild_id);
+        self.channels.remove(guild_id);
+        self.roles.remove(guild_id);
+    }
+}
+
+/// ステータスの表示順 (小さいほど上)
+fn status_rank(status: &str) -> u8 {
+    match status {
+        "online" => 0,
+        "idle" => 1,
+        "dnd" => 2,
+        _ => 3, // offline / invisible
     }
 }
 
+/// メンバーをステータス順 → 表示名 (ニックネーム > 表示名 > ユーザー名) 順に並べる
+fn sort_members(members: &mut [MemberWithPresence]) {
+    members.sort_by_cached_key(|m| {
+        let name = m.nick.as_deref()
+            .or(m.user.global_name.as_deref())
+            .unwrap_or(&m.user.username)
+            .to_lowercase();
+        (status_rank(&m.status), name)
+    });
+}
+
+fn is_thread(channel: &SimpleChannel) -> bool {
+    matches!(channel.kind.as_str(), "AnnouncementThread" | "PublicThread" | "PrivateThread")
+}
+
+/// REST (get_channels) と同じ並びにする: 通常チャンネルはDiscord順、スレッドはその後ろ
+fn sort_guild_channels(channels: &mut Vec<SimpleChannel>) {
+    let (mut sorted, threads): (Vec<_>, Vec<_>) = std::mem::take(channels).into_iter().partition(|c| !is_thread(c));
+    crate::services::social::sort_channels(&mut sorted);
+    sorted.extend(threads);
+    *channels = sorted;
+}
+
+/// ユーザーアバターのCDN URL (アニメーション判定は cdn_asset_url に任せる)
+pub fn user_avatar_url(user: &DiscordUser) -> Option<String> {
+    user.avatar.as_ref().map(|hash| crate::services::config::cdn_asset_url("avatars", &user.id, hash))
+}
+
 /// Tauriで管理するための型エイリアス
 pub type GuildStateHandle = Arc<Mutex<GuildMemberStore>>;
 
@@ -1,9 +1,10 @@
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use crate::services::models::DiscordUser;
 
-const API_BASE: &str = "https://discord.com/api/v10";
+use crate::services::config::api_base;
+use crate::services::error::AppError;
 
-pub async fn login(token: String) -> Result<(reqwest::Client, DiscordUser), String> {
+pub async fn login(token: String) -> Result<(reqwest::Client, DiscordUser), AppError> {
     let mut headers = HeaderMap::new();
     let mut auth_val = HeaderValue::from_str(&token).map_err(|_| "Invalid token format")?;
     auth_val.set_sensitive(true);
@@ -14,16 +15,35 @@ pub async fn login(token: String) -> Result<(reqwest::Client, DiscordUser), Stri
         .build()
         .map_err(|e| e.to_string())?;
 
-    let res = client.get(format!("{}/users/@me", API_BASE))
+    let res = client.get(format!("{}/users/@me", api_base()))
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("Login failed: Status {}", res.status()));
+        let status = res.status();
+        let mut err = AppError::from_response(res).await;
+        err.message = format!("Login failed: Status {}", status);
+        return Err(err);
     }
 
     let user: DiscordUser = res.json().await.map_err(|e| format!("Json parse error: {}", e))?;
 
     Ok((client, user))
 }
+
+/// トークンを検証してアカウント情報だけを返す (クライアントは保持しない)
+pub async fn validate_token(token: String) -> Result<DiscordUser, AppError> {
+    let (_, user) = login(token).await?;
+    Ok(user)
+}
+
+/// 現在のクライアントのトークンが拒否される (401) か確認する
+/// 401 の通知から呼ばれるので、send_throttled は使わない (再び通知されてしまうため)
+pub async fn is_token_rejected(client: &reqwest::Client) -> bool {
+    client.get(format!("{}/users/@me", api_base()))
+        .send()
+        .await
+        .map(|res| res.status() == reqwest::StatusCode::UNAUTHORIZED)
+        .unwrap_or(false)
+}
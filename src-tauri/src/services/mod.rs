@@ -1,3 +1,5 @@
+pub mod config;
+pub mod error;
 pub mod identity;
 pub mod social;
 
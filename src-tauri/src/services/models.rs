@@ -7,9 +7,12 @@ pub struct SimpleGuild {
     pub id: String,
     pub name: String,
     pub icon: Option<String>,
+    pub icon_url: Option<String>,
+    pub approximate_member_count: Option<u32>,
+    pub approximate_presence_count: Option<u32>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct SimpleChannel {
     pub id: String,
     pub name: String,
@@ -17,6 +20,12 @@ pub struct SimpleChannel {
     pub parent_id: Option<String>,
     pub position: i32,
     pub last_message_id: Option<String>,
+    pub tags: Vec<DiscordForumTag>,   // フォーラムチャンネルで使用可能なタグ
+    pub applied_tags: Vec<String>,    // スレッド (フォーラム投稿) に付与されたタグID
+    pub topic: Option<String>,
+    pub nsfw: bool,
+    pub rate_limit_per_user: u32,     // 低速モードの秒数 (0 なら無効)
+    pub permission_overwrites: Vec<PermissionOverwrite>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -34,6 +43,63 @@ pub struct SimpleMessage {
     pub referenced_message: Option<Box<SimpleMessage>>,
     pub message_snapshots: Vec<MessageSnapshot>,
     pub kind: String, // "Default", "UserJoin", "ChannelPin", etc.
+    #[serde(default)]
+    pub reactions: Vec<SimpleReaction>,
+    #[serde(default)]
+    pub components: Vec<SimpleComponent>,
+}
+
+/// メッセージコンポーネント (ボタン・セレクトメニュー)
+/// kind: 1 = ActionRow (子を components に持つ), 2 = Button, 3/5/6/7/8 = 各種セレクトメニュー
+/// APIの "type" からも、保存済みJSONの "kind" からも読めるようにしている
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SimpleComponent {
+    #[serde(alias = "type")]
+    pub kind: u8,
+    pub style: Option<u8>,        // ボタンの色 (1: Primary 〜 5: Link)
+    pub label: Option<String>,
+    pub custom_id: Option<String>,
+    pub url: Option<String>,      // Link ボタンのみ
+    pub emoji: Option<ComponentEmoji>,
+    pub placeholder: Option<String>,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub options: Vec<ComponentSelectOption>,
+    #[serde(default)]
+    pub components: Vec<SimpleComponent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComponentEmoji {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub animated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComponentSelectOption {
+    pub label: String,
+    pub value: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// 検索結果 (total_results はサーバー側の総ヒット数)
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchResult {
+    pub messages: Vec<SimpleMessage>,
+    pub total_results: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SimpleReaction {
+    pub emoji_name: Option<String>, // Unicode絵文字 or カスタム絵文字名
+    pub emoji_id: Option<String>,   // カスタム絵文字のみ
+    pub count: u32,
+    pub me: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -59,6 +125,54 @@ pub struct SimpleRole {
     pub hoist: bool,
 }
 
+/// ギルドの詳細 (ギルドを開いたときに表示するメタデータ)
+#[derive(Serialize, Clone)]
+pub struct GuildDetail {
+    pub id: String,
+    pub name: String,
+    pub icon_url: Option<String>,
+    pub banner_url: Option<String>,
+    pub description: Option<String>,
+    pub owner_id: String,
+    pub features: Vec<String>, // "COMMUNITY", "NEWS" など
+    pub premium_tier: u8,      // ブーストレベル (0〜3)
+    pub premium_subscription_count: u32,
+    pub approximate_member_count: Option<u32>,
+    pub approximate_presence_count: Option<u32>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SimpleInvite {
+    pub code: String,
+    pub uses: u32,
+    pub max_uses: u32,
+    pub expires_at: Option<String>,
+}
+
+/// 監査ログのエントリ (簡略化)
+#[derive(Serialize, Clone)]
+pub struct SimpleAuditLogEntry {
+    pub id: String,
+    pub action_type: u32,
+    pub user_id: Option<String>,   // 操作したユーザー
+    pub target_id: Option<String>, // 対象 (ユーザー・チャンネル・ロールなど)
+    pub reason: Option<String>,
+    pub changes: Vec<AuditLogChange>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AuditLogChange {
+    pub key: String,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SimpleThreadMember {
+    pub user_id: String,
+    pub join_timestamp: String,
+}
+
 #[derive(Serialize, Clone)]
 pub struct SimpleMember {
     pub user: DiscordUser, // Reuse DiscordUser for simplicity as it has id, username, avatar
@@ -87,6 +201,40 @@ pub struct DiscordEmbed {
     pub footer: Option<DiscordEmbedFooter>,
     pub image: Option<DiscordEmbedImage>,
     pub thumbnail: Option<DiscordEmbedThumbnail>,
+    pub author: Option<DiscordEmbedAuthor>,
+    #[serde(default)]
+    pub fields: Vec<DiscordEmbedField>,
+    pub timestamp: Option<String>, // ISO8601
+    pub video: Option<DiscordEmbedVideo>,
+    pub provider: Option<DiscordEmbedProvider>, // リンクプレビューの提供元 (YouTube など)
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct DiscordEmbedVideo {
+    pub url: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct DiscordEmbedProvider {
+    pub name: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct DiscordEmbedAuthor {
+    pub name: String,
+    pub url: Option<String>,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct DiscordEmbedField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub inline: bool,
 }
 
 #[derive(Deserialize, Debug, Serialize, Clone)]
@@ -113,6 +261,31 @@ pub struct DiscordUser {
     pub username: String,
     pub discriminator: String,
     pub avatar: Option<String>,
+    #[serde(default)]
+    pub global_name: Option<String>, // 表示名 (新ユーザー名システム)
+    #[serde(default)]
+    pub bot: bool,
+}
+
+/// ボイスリージョン (/voice/regions)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct VoiceRegion {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub optimal: bool,    // クライアントに最も近いリージョン
+    #[serde(default)]
+    pub deprecated: bool,
+}
+
+/// /users/@me/settings のうち利用する項目
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DiscordUserSettings {
+    pub locale: Option<String>,
+    pub status: Option<String>,
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub developer_mode: bool,
 }
 
 #[derive(Deserialize, Debug)]
@@ -120,6 +293,27 @@ pub struct DiscordGuild {
     pub id: String,
     pub name: String,
     pub icon: Option<String>,
+    // with_counts=true 指定時のみ含まれる
+    pub approximate_member_count: Option<u32>,
+    pub approximate_presence_count: Option<u32>,
+}
+
+/// /guilds/{guild_id} の詳細情報
+#[derive(Deserialize, Debug)]
+pub struct DiscordGuildDetail {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub banner: Option<String>,
+    pub description: Option<String>,
+    pub owner_id: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub premium_tier: u8,
+    pub premium_subscription_count: Option<u32>,
+    pub approximate_member_count: Option<u32>,
+    pub approximate_presence_count: Option<u32>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -132,6 +326,35 @@ pub struct DiscordChannel {
     pub position: Option<i32>,
     pub thread_metadata: Option<DiscordThreadMetadata>,
     pub last_message_id: Option<String>,
+    pub available_tags: Option<Vec<DiscordForumTag>>, // Forum (type 15) のみ
+    pub applied_tags: Option<Vec<String>>,            // フォーラム投稿スレッドのみ
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub nsfw: bool,
+    pub rate_limit_per_user: Option<u32>,
+    #[serde(default)]
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+}
+
+/// チャンネルの権限上書き
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct PermissionOverwrite {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: u8, // 0: ロール, 1: メンバー
+    pub allow: String, // 権限ビット (文字列の数値)
+    pub deny: String,
+}
+
+/// フォーラムタグ
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct DiscordForumTag {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub moderated: bool,
+    pub emoji_id: Option<String>,
+    pub emoji_name: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -155,6 +378,24 @@ pub struct DiscordMessage {
     pub message_snapshots: Option<Vec<DiscordMessageSnapshot>>,
     #[serde(rename = "type", default)]
     pub kind: u8,
+    #[serde(default)]
+    pub reactions: Option<Vec<DiscordReaction>>,
+    #[serde(default)]
+    pub components: Vec<SimpleComponent>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiscordReaction {
+    pub count: u32,
+    #[serde(default)]
+    pub me: bool,
+    pub emoji: DiscordReactionEmoji,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiscordReactionEmoji {
+    pub id: Option<String>,
+    pub name: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -181,6 +422,39 @@ pub struct DiscordRole {
     // permissions, managed, mentionable... (omitted)
 }
 
+#[derive(Deserialize, Debug)]
+pub struct DiscordThreadMember {
+    pub user_id: Option<String>,
+    pub join_timestamp: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiscordAuditLog {
+    #[serde(default)]
+    pub audit_log_entries: Vec<DiscordAuditLogEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiscordAuditLogEntry {
+    pub id: String,
+    pub action_type: u32,
+    pub user_id: Option<String>,
+    pub target_id: Option<String>,
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub changes: Vec<AuditLogChange>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiscordInvite {
+    pub code: String,
+    #[serde(default)]
+    pub uses: u32,
+    #[serde(default)]
+    pub max_uses: u32,
+    pub expires_at: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct DiscordMember {
     pub user: Option<DiscordUser>, // Sometimes minimal objects missing user? usually present in member list
@@ -235,6 +509,9 @@ pub struct MemberWithPresence {
     pub user: DiscordUser,
     pub roles: Vec<String>,
     pub nick: Option<String>,
+    pub guild_avatar: Option<String>, // サーバー専用アバターのCDN URL (未設定なら avatar_url を使う)
+    #[serde(default)]
+    pub avatar_url: Option<String>, // ユーザーアバターのCDN URL
     pub joined_at: String,
     pub status: String,
     pub activities: Vec<Activity>,
@@ -255,6 +532,46 @@ pub struct VoiceState {
     pub mute: bool,
     #[serde(default)]
     pub deaf: bool,
+    #[serde(default)]
+    pub self_stream: bool, // 画面共有 (Go Live) 中
+    #[serde(default)]
+    pub self_video: bool,  // カメラON
+}
+
+/// ギルドの通知設定 (READY の user_guild_settings)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GuildNotificationSettings {
+    pub guild_id: Option<String>, // DM の設定は null
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub message_notifications: u8, // 0: すべて, 1: メンションのみ, 2: なし, 3: ギルドの既定値
+    #[serde(default)]
+    pub channel_overrides: Vec<ChannelNotificationOverride>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChannelNotificationOverride {
+    pub channel_id: String,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub message_notifications: u8,
+}
+
+/// ボイスチャンネル参加者 (ボイス状態とメンバー情報を結合したもの)
+#[derive(Serialize, Clone, Debug)]
+pub struct VoiceParticipant {
+    pub user_id: String,
+    pub user: Option<DiscordUser>,    // メンバーストアに未登録なら None
+    pub nick: Option<String>,
+    pub guild_avatar: Option<String>, // サーバー専用アバターのCDN URL
+    pub self_mute: bool,
+    pub self_deaf: bool,
+    pub mute: bool,
+    pub deaf: bool,
+    pub self_stream: bool,
+    pub self_video: bool,
 }
 
 /// タイピング開始イベント
@@ -272,6 +589,7 @@ pub struct DiscordDMChannel {
     #[serde(rename = "type")]
     pub kind: u8,
     pub last_message_id: Option<String>,
+    #[serde(default)]
     pub recipients: Vec<DiscordUser>,
     pub name: Option<String>,
     pub icon: Option<String>,
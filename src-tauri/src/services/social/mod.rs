@@ -1,11 +1,28 @@
 use crate::services::models::{
     DiscordGuild, DiscordChannel, DiscordMessage, DiscordRole, DiscordMember,
     SimpleGuild, SimpleChannel, SimpleMessage, SimpleRole, SimpleMember,
-    MessageSnapshot, SimpleMessageSnapshotData, DiscordUser, DiscordDMChannel
+    MessageSnapshot, SimpleMessageSnapshotData, DiscordUser, DiscordUserSettings, DiscordDMChannel,
+    DiscordInvite, SimpleInvite, DiscordThreadMember, SimpleThreadMember,
+    DiscordReaction, SimpleReaction, SearchResult, DiscordAuditLog, SimpleAuditLogEntry, VoiceRegion,
+    DiscordGuildDetail, GuildDetail, DiscordEmbed
 };
-use reqwest::Client;
+use crate::services::state::ApiClient as Client;
 
-const API_BASE: &str = "https://discord.com/api/v10";
+use crate::services::config::{api_base, cdn_asset_url};
+use crate::services::error::{AppError, ErrorKind};
+use std::future::Future;
+
+/// DiscordState の同時実行数制限を通してリクエストを送信する
+/// (social 内の REST 呼び出しはすべて send() の代わりにこれを使う)
+trait ThrottledSend {
+    fn send_throttled(self, client: &Client) -> impl Future<Output = Result<reqwest::Response, reqwest::Error>> + Send + '_;
+}
+
+impl ThrottledSend for reqwest::RequestBuilder {
+    fn send_throttled(self, client: &Client) -> impl Future<Output = Result<reqwest::Response, reqwest::Error>> + Send + '_ {
+        client.send_throttled(self)
+    }
+}
 
 fn map_channel_type(kind: u8) -> String {
     match kind {
@@ -23,6 +40,66 @@ fn map_channel_type(kind: u8) -> String {
     }
 }
 
+pub(crate) fn map_channel(c: DiscordChannel, fallback_name: &str) -> SimpleChannel {
+    SimpleChannel {
+        id: c.id,
+        name: c.name.unwrap_or_else(|| fallback_name.to_string()),
+        kind: map_channel_type(c.kind),
+        parent_id: c.parent_id,
+        position: c.position.unwrap_or(0),
+        last_message_id: c.last_message_id,
+        tags: c.available_tags.unwrap_or_default(),
+        applied_tags: c.applied_tags.unwrap_or_default(),
+        topic: c.topic,
+        nsfw: c.nsfw,
+        rate_limit_per_user: c.rate_limit_per_user.unwrap_or(0),
+        permission_overwrites: c.permission_overwrites,
+    }
+}
+
+/// Discordクライアントと同じ順序にチャンネルを並び替える
+/// カテゴリ無しチャンネル → カテゴリ(position順) → 各カテゴリの子 (テキスト系 → ボイス系, position順)
+pub(crate) fn sort_channels(channels: &mut [SimpleChannel]) {
+    use std::collections::HashMap;
+
+    let category_positions: HashMap<String, i32> = channels.iter()
+        .filter(|c| c.kind == "Category")
+        .map(|c| (c.id.clone(), c.position))
+        .collect();
+
+    let snowflake = |id: &str| id.parse::<u64>().unwrap_or(0);
+
+    channels.sort_by_key(|c| {
+        if c.kind == "Category" {
+            // (カテゴリ位置, カテゴリ自身が先頭, ...)
+            (1, c.position, snowflake(&c.id), 0, 0, 0, 0)
+        } else {
+            let is_voice = matches!(c.kind.as_str(), "Voice" | "Type(13)") as i32;
+            match c.parent_id.as_ref().and_then(|p| category_positions.get(p).map(|pos| (p, *pos))) {
+                Some((parent, pos)) => (1, pos, snowflake(parent), 1, is_voice, c.position, snowflake(&c.id)),
+                None => (0, 0, 0, 1, is_voice, c.position, snowflake(&c.id)),
+            }
+        }
+    });
+}
+
+/// ギルドのチャンネル一覧を変換してDiscord順に並べる (REST / GUILD_CREATE 共通)
+pub fn map_guild_channels(channels: Vec<DiscordChannel>) -> Vec<SimpleChannel> {
+    let mut channels: Vec<SimpleChannel> = channels.into_iter().map(|c| map_channel(c, "Unknown")).collect();
+    sort_channels(&mut channels);
+    channels
+}
+
+pub fn map_role(r: DiscordRole) -> SimpleRole {
+    SimpleRole {
+        id: r.id,
+        name: r.name,
+        color: r.color,
+        position: r.position,
+        hoist: r.hoist,
+    }
+}
+
 fn map_message_type(kind: u8) -> String {
     match kind {
         0 | 19 => "Default".to_string(),
@@ -39,55 +116,184 @@ fn map_message_type(kind: u8) -> String {
     }
 }
 
-pub async fn fetch_guilds(client: &Client) -> Result<Vec<SimpleGuild>, String> {
-    let res = client.get(format!("{}/users/@me/guilds", API_BASE))
-        .send()
+/// DiscordMessage (API) を SimpleMessage (フロントエンド/DB用) に変換
+fn map_message(m: DiscordMessage, guild_id: &str) -> SimpleMessage {
+    SimpleMessage {
+        id: m.id,
+        guild_id: guild_id.to_string(),
+        channel_id: m.channel_id,
+        content: m.content,
+        author: m.author.username,
+        author_id: m.author.id,
+        timestamp: m.timestamp,
+        embeds: m.embeds,
+        attachments: m.attachments,
+        referenced_message: m.referenced_message.map(|rm| Box::new(SimpleMessage {
+            id: rm.id,
+            guild_id: guild_id.to_string(),
+            channel_id: rm.channel_id,
+            content: rm.content,
+            author: rm.author.username,
+            author_id: rm.author.id,
+            timestamp: rm.timestamp,
+            embeds: rm.embeds,
+            attachments: rm.attachments,
+            referenced_message: None, // Avoid infinite recursion
+            message_snapshots: vec![],
+            kind: map_message_type(rm.kind),
+            reactions: map_reactions(rm.reactions),
+            components: rm.components,
+        })),
+        message_snapshots: m.message_snapshots.unwrap_or_default().into_iter().map(|s| MessageSnapshot {
+            message: SimpleMessageSnapshotData {
+                content: s.message.content,
+                author: s.message.author.map(|a| a.username).unwrap_or_else(|| "Unknown".to_string()),
+                timestamp: s.message.timestamp,
+                embeds: s.message.embeds,
+                attachments: s.message.attachments,
+            }
+        }).collect(),
+        kind: map_message_type(m.kind),
+        reactions: map_reactions(m.reactions),
+        components: m.components,
+    }
+}
+
+fn map_reactions(reactions: Option<Vec<DiscordReaction>>) -> Vec<SimpleReaction> {
+    reactions.unwrap_or_default().into_iter().map(|r| SimpleReaction {
+        emoji_name: r.emoji.name,
+        emoji_id: r.emoji.id,
+        count: r.count,
+        me: r.me,
+    }).collect()
+}
+
+/// 監査ログの理由を X-Audit-Log-Reason ヘッダーに付与 (URLエンコード必須)
+fn with_audit_log_reason(req: reqwest::RequestBuilder, reason: Option<&str>) -> reqwest::RequestBuilder {
+    match reason.filter(|r| !r.is_empty()) {
+        Some(r) => req.header("X-Audit-Log-Reason", urlencoding::encode(r).into_owned()),
+        None => req,
+    }
+}
+
+pub async fn fetch_guilds(client: &Client) -> Result<Vec<SimpleGuild>, AppError> {
+    let res = client.get(format!("{}/users/@me/guilds?with_counts=true", api_base()))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let guilds: Vec<DiscordGuild> = res.json().await.map_err(|e| e.to_string())?;
 
     Ok(guilds.into_iter().map(|g| SimpleGuild {
+        icon_url: g.icon.as_ref().map(|hash| cdn_asset_url("icons", &g.id, hash)),
         id: g.id,
         name: g.name,
         icon: g.icon,
+        approximate_member_count: g.approximate_member_count,
+        approximate_presence_count: g.approximate_presence_count,
     }).collect())
 }
 
-pub async fn fetch_channels(client: &Client, guild_id: String) -> Result<Vec<SimpleChannel>, String> {
-    let res = client.get(format!("{}/guilds/{}/channels", API_BASE, guild_id))
-        .send()
+/// 単一ギルドの詳細を取得 (オーナー・機能フラグ・ブーストレベルなど)
+pub async fn fetch_guild(client: &Client, guild_id: String) -> Result<GuildDetail, AppError> {
+    let res = client.get(format!("{}/guilds/{}?with_counts=true", api_base(), guild_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let g: DiscordGuildDetail = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(GuildDetail {
+        icon_url: g.icon.as_ref().map(|hash| cdn_asset_url("icons", &g.id, hash)),
+        banner_url: g.banner.as_ref().map(|hash| cdn_asset_url("banners", &g.id, hash)),
+        id: g.id,
+        name: g.name,
+        description: g.description,
+        owner_id: g.owner_id,
+        features: g.features,
+        premium_tier: g.premium_tier,
+        premium_subscription_count: g.premium_subscription_count.unwrap_or(0),
+        approximate_member_count: g.approximate_member_count,
+        approximate_presence_count: g.approximate_presence_count,
+    })
+}
+
+/// サーバーから脱退する
+pub async fn leave_guild(client: &Client, guild_id: String) -> Result<(), AppError> {
+    let res = client.delete(format!("{}/users/@me/guilds/{}", api_base(), guild_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        // オーナーは脱退できない (400 / code 50055 Invalid Guild)
+        if status.as_u16() == 400 && body.contains("50055") {
+            return Err(AppError {
+                kind: ErrorKind::Forbidden,
+                message: "Server owners cannot leave their own server. Transfer ownership or delete the server instead.".to_string(),
+                status: Some(400),
+                retry_after: None,
+            });
+        }
+        return Err(AppError::from_status(status, body));
+    }
+
+    Ok(())
+}
+
+/// チャンネルのミュート状態をDiscordの通知設定 (channel_overrides) に反映
+pub async fn update_channel_muted(client: &Client, guild_id: String, channel_id: String, muted: bool) -> Result<(), AppError> {
+    let body = serde_json::json!({
+        "channel_overrides": {
+            channel_id: { "muted": muted }
+        }
+    });
+    let res = client.patch(format!("{}/users/@me/guilds/{}/settings", api_base(), guild_id))
+        .json(&body)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+pub async fn fetch_channels(client: &Client, guild_id: String) -> Result<Vec<SimpleChannel>, AppError> {
+    let res = client.get(format!("{}/guilds/{}/channels", api_base(), guild_id))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let channels: Vec<DiscordChannel> = res.json().await.map_err(|e| e.to_string())?;
 
-    Ok(channels.into_iter().map(|c| SimpleChannel {
-        id: c.id,
-        name: c.name.unwrap_or_else(|| "Unknown".to_string()),
-        kind: map_channel_type(c.kind),
-        parent_id: c.parent_id,
-        position: c.position.unwrap_or(0),
-        last_message_id: c.last_message_id,
-    }).collect())
+    Ok(map_guild_channels(channels))
 }
 
-pub async fn fetch_active_threads(client: &Client, guild_id: String) -> Result<Vec<SimpleChannel>, String> {
-    let res = client.get(format!("{}/guilds/{}/threads/active", API_BASE, guild_id))
-        .send()
+pub async fn fetch_active_threads(client: &Client, guild_id: String) -> Result<Vec<SimpleChannel>, AppError> {
+    let res = client.get(format!("{}/guilds/{}/threads/active", api_base(), guild_id))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     // Active threads response: { "threads": [ ... ], "members": [ ... ] }
@@ -99,14 +305,7 @@ pub async fn fetch_active_threads(client: &Client, guild_id: String) -> Result<V
         println!("Found active threads: {}", threads.len());
         for t_value in threads {
              if let Ok(c) = serde_json::from_value::<DiscordChannel>(t_value.clone()) {
-                simple_channels.push(SimpleChannel {
-                    id: c.id,
-                    name: c.name.unwrap_or_else(|| "Unknown Thread".to_string()),
-                    kind: map_channel_type(c.kind),
-                    parent_id: c.parent_id,
-                    position: c.position.unwrap_or(0),
-                    last_message_id: c.last_message_id,
-                });
+                simple_channels.push(map_channel(c, "Unknown Thread"));
              } else {
                  println!("Failed to parse thread: {:?}", t_value);
              }
@@ -118,14 +317,14 @@ pub async fn fetch_active_threads(client: &Client, guild_id: String) -> Result<V
     Ok(simple_channels)
 }
 
-pub async fn fetch_archived_threads(client: &Client, channel_id: String) -> Result<Vec<SimpleChannel>, String> {
-    let res = client.get(format!("{}/channels/{}/threads/archived/public", API_BASE, channel_id))
-        .send()
+pub async fn fetch_archived_threads(client: &Client, channel_id: String) -> Result<Vec<SimpleChannel>, AppError> {
+    let res = client.get(format!("{}/channels/{}/threads/archived/public", api_base(), channel_id))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-         return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+         return Err(AppError::from_response(res).await);
     }
 
     let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
@@ -135,14 +334,8 @@ pub async fn fetch_archived_threads(client: &Client, channel_id: String) -> Resu
         for t_value in threads {
              if let Ok(c) = serde_json::from_value::<DiscordChannel>(t_value.clone()) {
                 // Ensure parent_id is set to channel_id (it should be, but just in case)
-                let mut channel = SimpleChannel {
-                    id: c.id,
-                    name: c.name.unwrap_or_else(|| "Unknown Archived Thread".to_string()),
-                    kind: map_channel_type(c.kind),
-                    parent_id: Some(channel_id.clone()), // Explicitly link to parent
-                    position: c.position.unwrap_or(0),
-                    last_message_id: c.last_message_id,
-                };
+                let mut channel = map_channel(c, "Unknown Archived Thread");
+                channel.parent_id = Some(channel_id.clone()); // Explicitly link to parent
                 // Archived threads data sometimes misses parent_id or it's implied
                 if channel.parent_id.is_none() {
                     channel.parent_id = Some(channel_id.clone());
@@ -155,20 +348,99 @@ pub async fn fetch_archived_threads(client: &Client, channel_id: String) -> Resu
     Ok(simple_channels)
 }
 
-pub async fn fetch_forum_active_threads(client: &Client, guild_id: String, channel_id: String) -> Result<Vec<SimpleChannel>, String> {
+/// スレッドを作成 (message_id 指定時はメッセージから、なければチャンネル直下に公開スレッドを作成)
+pub async fn create_thread(client: &Client, channel_id: String, name: String, message_id: Option<String>) -> Result<SimpleChannel, AppError> {
+    let (url, payload) = match message_id {
+        Some(mid) => (
+            format!("{}/channels/{}/messages/{}/threads", api_base(), channel_id, mid),
+            serde_json::json!({ "name": name }),
+        ),
+        None => (
+            format!("{}/channels/{}/threads", api_base(), channel_id),
+            serde_json::json!({ "name": name, "type": 11 }), // 11 = PublicThread
+        ),
+    };
+
+    let res = client.post(&url)
+        .json(&payload)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let c: DiscordChannel = res.json().await.map_err(|e| e.to_string())?;
+
+    let mut channel = map_channel(c, "Unknown Thread");
+    if channel.parent_id.is_none() {
+        channel.parent_id = Some(channel_id);
+    }
+    Ok(channel)
+}
+
+/// スレッドに参加 (メンバー限定の更新を受け取るために必要)
+pub async fn join_thread(client: &Client, channel_id: String) -> Result<(), AppError> {
+    let res = client.put(format!("{}/channels/{}/thread-members/@me", api_base(), channel_id))
+        .header(reqwest::header::CONTENT_LENGTH, 0)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// スレッドから退出
+pub async fn leave_thread(client: &Client, channel_id: String) -> Result<(), AppError> {
+    let res = client.delete(format!("{}/channels/{}/thread-members/@me", api_base(), channel_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+pub async fn fetch_thread_members(client: &Client, channel_id: String) -> Result<Vec<SimpleThreadMember>, AppError> {
+    let res = client.get(format!("{}/channels/{}/thread-members", api_base(), channel_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let members: Vec<DiscordThreadMember> = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(members.into_iter().filter_map(|m| m.user_id.map(|user_id| SimpleThreadMember {
+        user_id,
+        join_timestamp: m.join_timestamp,
+    })).collect())
+}
+
+pub async fn fetch_forum_active_threads(client: &Client, guild_id: String, channel_id: String) -> Result<Vec<SimpleChannel>, AppError> {
      // Use Search API to find threads in the channel (workaround for user token)
      // Query: channel_id={channel_id}
-     let url = format!("{}/guilds/{}/messages/search?channel_id={}", API_BASE, guild_id, channel_id);
+     let url = format!("{}/guilds/{}/messages/search?channel_id={}", api_base(), guild_id, channel_id);
      
      println!("[fetch_forum_active_threads] Requesting URL: {}", url);
 
      let res = client.get(&url)
-        .send()
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-         return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+         return Err(AppError::from_response(res).await);
     }
 
     let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
@@ -184,14 +456,8 @@ pub async fn fetch_forum_active_threads(client: &Client, guild_id: String, chann
                 let is_archived = c.thread_metadata.as_ref().map(|m| m.archived).unwrap_or(false);
                 
                 if !is_archived {
-                    let mut channel = SimpleChannel {
-                        id: c.id,
-                        name: c.name.unwrap_or_else(|| "Unknown Thread".to_string()),
-                        kind: map_channel_type(c.kind),
-                        parent_id: Some(channel_id.clone()),
-                        position: c.position.unwrap_or(0),
-                        last_message_id: c.last_message_id,
-                    };
+                    let mut channel = map_channel(c, "Unknown Thread");
+                    channel.parent_id = Some(channel_id.clone());
                     if channel.parent_id.is_none() {
                         channel.parent_id = Some(channel_id.clone());
                     }
@@ -209,28 +475,106 @@ pub async fn fetch_forum_active_threads(client: &Client, guild_id: String, chann
     Ok(simple_channels)
 }
 
-pub async fn fetch_roles(client: &Client, guild_id: String) -> Result<Vec<SimpleRole>, String> {
-    let res = client.get(format!("{}/guilds/{}/roles", API_BASE, guild_id))
-        .send()
+pub async fn fetch_roles(client: &Client, guild_id: String) -> Result<Vec<SimpleRole>, AppError> {
+    let res = client.get(format!("{}/guilds/{}/roles", api_base(), guild_id))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let roles: Vec<DiscordRole> = res.json().await.map_err(|e| e.to_string())?;
 
-    Ok(roles.into_iter().map(|r| SimpleRole {
-        id: r.id,
-        name: r.name,
-        color: r.color,
-        position: r.position,
-        hoist: r.hoist,
+    Ok(roles.into_iter().map(map_role).collect())
+}
+
+pub async fn fetch_invites(client: &Client, channel_id: String) -> Result<Vec<SimpleInvite>, AppError> {
+    let res = client.get(format!("{}/channels/{}/invites", api_base(), channel_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Manage Channel is required to view invites"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let invites: Vec<DiscordInvite> = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(invites.into_iter().map(|i| SimpleInvite {
+        code: i.code,
+        uses: i.uses,
+        max_uses: i.max_uses,
+        expires_at: i.expires_at,
+    }).collect())
+}
+
+/// 監査ログを取得 (action_type で種類を絞り込み可能 / limit: 1〜100)
+pub async fn fetch_audit_log(client: &Client, guild_id: String, action_type: Option<u32>, limit: u32) -> Result<Vec<SimpleAuditLogEntry>, AppError> {
+    let mut url = format!("{}/guilds/{}/audit-logs?limit={}", api_base(), guild_id, limit.clamp(1, 100));
+    if let Some(action_type) = action_type {
+        url.push_str(&format!("&action_type={}", action_type));
+    }
+
+    let res = client.get(url)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: View Audit Log is required"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let log: DiscordAuditLog = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(log.audit_log_entries.into_iter().map(|e| SimpleAuditLogEntry {
+        id: e.id,
+        action_type: e.action_type,
+        user_id: e.user_id,
+        target_id: e.target_id,
+        reason: e.reason,
+        changes: e.changes,
     }).collect())
 }
 
-pub async fn fetch_members(client: &Client, guild_id: String) -> Result<Vec<SimpleMember>, String> {
+/// 招待を作成 (max_age: 秒, 0 = 無期限 / max_uses: 0 = 無制限)
+pub async fn create_invite(client: &Client, channel_id: String, max_age: u32, max_uses: u32) -> Result<SimpleInvite, AppError> {
+    let payload = serde_json::json!({
+        "max_age": max_age,
+        "max_uses": max_uses,
+    });
+
+    let res = client.post(format!("{}/channels/{}/invites", api_base(), channel_id))
+        .json(&payload)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Create Invite is not allowed in this channel"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let i: DiscordInvite = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(SimpleInvite {
+        code: i.code,
+        uses: i.uses,
+        max_uses: i.max_uses,
+        expires_at: i.expires_at,
+    })
+}
+
+pub async fn fetch_members(client: &Client, guild_id: String) -> Result<Vec<SimpleMember>, AppError> {
     // ユーザートークンではメンバー一覧APIにアクセスできない
     // - /guilds/{guild_id}/members は Bot専用 (403 Missing Access)
     // - /guilds/{guild_id}/members/search は検索用で空クエリ不可 (400)
@@ -247,19 +591,127 @@ pub async fn fetch_members(client: &Client, guild_id: String) -> Result<Vec<Simp
     Ok(vec![])
 }
 
-pub async fn fetch_dms(client: &Client) -> Result<Vec<SimpleChannel>, String> {
-    let res = client.get(format!("{}/users/@me/channels", API_BASE))
-        .send()
+/// メンバーをキック
+pub async fn kick_member(client: &Client, guild_id: String, user_id: String, reason: Option<String>) -> Result<(), AppError> {
+    let req = client.delete(format!("{}/guilds/{}/members/{}", api_base(), guild_id, user_id));
+    let res = with_audit_log_reason(req, reason.as_deref())
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Kick Members is required"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// メンバーをBAN (delete_message_seconds: 過去何秒分のメッセージを削除するか、最大7日)
+pub async fn ban_member(client: &Client, guild_id: String, user_id: String, delete_message_seconds: u32, reason: Option<String>) -> Result<(), AppError> {
+    let body = serde_json::json!({
+        "delete_message_seconds": delete_message_seconds.min(604800),
+    });
+
+    let req = client.put(format!("{}/guilds/{}/bans/{}", api_base(), guild_id, user_id)).json(&body);
+    let res = with_audit_log_reason(req, reason.as_deref())
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Ban Members is required"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// ニックネームを変更 (user_id に "@me" を指定すると自分のニックネーム)
+/// nick が None の場合はニックネームをリセット
+pub async fn set_nickname(client: &Client, guild_id: String, user_id: String, nick: Option<String>) -> Result<DiscordMember, AppError> {
+    let body = serde_json::json!({ "nick": nick });
+
+    let res = client.patch(format!("{}/guilds/{}/members/{}", api_base(), guild_id, user_id))
+        .json(&body)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        let msg = if user_id == "@me" {
+            "Missing permission: Change Nickname is required"
+        } else {
+            "Missing permission: Manage Nicknames is required to edit other members"
+        };
+        return Err(AppError::forbidden(msg));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let member: DiscordMember = res.json().await.map_err(|e| e.to_string())?;
+    Ok(member)
+}
+
+/// メンバーにロールを付与
+pub async fn add_member_role(client: &Client, guild_id: String, user_id: String, role_id: String, reason: Option<String>) -> Result<(), AppError> {
+    let req = client.put(format!("{}/guilds/{}/members/{}/roles/{}", api_base(), guild_id, user_id, role_id));
+    let res = with_audit_log_reason(req, reason.as_deref())
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Manage Roles is required (and the role must be below your highest role)"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// メンバーからロールを剥奪
+pub async fn remove_member_role(client: &Client, guild_id: String, user_id: String, role_id: String, reason: Option<String>) -> Result<(), AppError> {
+    let req = client.delete(format!("{}/guilds/{}/members/{}/roles/{}", api_base(), guild_id, user_id, role_id));
+    let res = with_audit_log_reason(req, reason.as_deref())
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Manage Roles is required (and the role must be below your highest role)"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+pub async fn fetch_dms(client: &Client) -> Result<Vec<SimpleChannel>, AppError> {
+    let res = client.get(format!("{}/users/@me/channels", api_base()))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let channels: Vec<DiscordDMChannel> = res.json().await.map_err(|e| e.to_string())?;
 
-    Ok(channels.into_iter().map(|c| {
+    Ok(map_dm_channels(channels))
+}
+
+/// DMチャンネルを SimpleChannel に変換 (REST の /users/@me/channels と READY の private_channels で共用)
+pub fn map_dm_channels(channels: Vec<DiscordDMChannel>) -> Vec<SimpleChannel> {
+    channels.into_iter().map(|c| {
         // DM name fallback to recipients
         let name = if let Some(n) = c.name {
             n
@@ -274,145 +726,116 @@ pub async fn fetch_dms(client: &Client) -> Result<Vec<SimpleChannel>, String> {
             parent_id: None,
             position: 0,
             last_message_id: c.last_message_id,
+            tags: vec![],
+            applied_tags: vec![],
+            topic: None,
+            nsfw: false,
+            rate_limit_per_user: 0,
+            permission_overwrites: vec![],
         }
-    }).collect())
+    }).collect()
 }
 
-pub async fn fetch_current_user(client: &Client) -> Result<DiscordUser, String> {
-    let res = client.get(format!("{}/users/@me", API_BASE))
-        .send()
+pub async fn fetch_current_user(client: &Client) -> Result<DiscordUser, AppError> {
+    let res = client.get(format!("{}/users/@me", api_base()))
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let user: DiscordUser = res.json().await.map_err(|e| e.to_string())?;
     Ok(user)
 }
 
-pub async fn fetch_messages(client: &Client, channel_id: String, before_id: Option<String>) -> Result<Vec<SimpleMessage>, String> {
-    let url = match before_id {
-        Some(before) => format!("{}/channels/{}/messages?limit=50&before={}", API_BASE, channel_id, before),
-        None => format!("{}/channels/{}/messages?limit=50", API_BASE, channel_id),
+pub async fn fetch_user_settings(client: &Client) -> Result<DiscordUserSettings, AppError> {
+    let res = client.get(format!("{}/users/@me/settings", api_base()))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let settings: DiscordUserSettings = res.json().await.map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// ボイスリージョン一覧を取得 (遅延の原因切り分け用)
+pub async fn fetch_voice_regions(client: &Client) -> Result<Vec<VoiceRegion>, AppError> {
+    let res = client.get(format!("{}/voice/regions", api_base()))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let regions: Vec<VoiceRegion> = res.json().await.map_err(|e| e.to_string())?;
+    Ok(regions)
+}
+
+pub async fn fetch_messages_with_guid(client: &Client, guild_id: String, channel_id: String, before_id: Option<String>) -> Result<Vec<SimpleMessage>, AppError> {
+     let url = match before_id {
+        Some(before) => format!("{}/channels/{}/messages?limit=50&before={}", api_base(), channel_id, before),
+        None => format!("{}/channels/{}/messages?limit=50", api_base(), channel_id),
     };
 
     let res = client.get(&url)
-        .send()
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let messages: Vec<DiscordMessage> = res.json().await.map_err(|e| e.to_string())?;
 
-    // Note: SimpleMessage requires guild_id, but generic channel fetch might not have it contextually if not passed.
-    // However, the function caller usually knows the guild_id.
-    // We will return generic SimpleMesssage with "unknown" guild_id if strictly necessary, 
-    // OR update the signature of fetch_messages to take guild_id.
-    // But wait, fetch_messages_with_guid exists below. 
-    // fetch_messages seems redundant or needs to be removed/merged.
-    // For now, let's map it using empty string for guild_id as placeholder or remove this function if unused.
-    // The previous implementation had a "return Ok(vec![])" placeholder.
-    
-    // Better approach: Since we have fetch_messages_with_guid, let's just make this function behave correctly or delegate.
-    // But simpler: just map it.
-    
-    Ok(messages.into_iter().map(|m| SimpleMessage {
-        id: m.id,
-        guild_id: "".to_string(), // Missing context
-        channel_id: m.channel_id,
-        content: m.content,
-        author: m.author.username,
-        author_id: m.author.id,
-        timestamp: m.timestamp,
-        embeds: m.embeds,
-        attachments: m.attachments,
-        referenced_message: m.referenced_message.map(|rm| Box::new(SimpleMessage {
-            id: rm.id,
-            guild_id: "".to_string(), // Missing context
-            channel_id: rm.channel_id,
-            content: rm.content,
-            author: rm.author.username,
-            author_id: rm.author.id,
-            timestamp: rm.timestamp,
-            embeds: rm.embeds,
-            attachments: rm.attachments,
-            referenced_message: None, // Avoid infinite recursion
-            message_snapshots: vec![],
-            kind: map_message_type(rm.kind),
-        })),
-        message_snapshots: m.message_snapshots.unwrap_or_default().into_iter().map(|s| MessageSnapshot {
-            message: SimpleMessageSnapshotData {
-                content: s.message.content,
-                author: s.message.author.map(|a| a.username).unwrap_or_else(|| "Unknown".to_string()),
-                timestamp: s.message.timestamp,
-                embeds: s.message.embeds,
-                attachments: s.message.attachments,
-            }
-        }).collect(),
-        kind: map_message_type(m.kind),
-    }).collect())
+    Ok(messages.into_iter().map(|m| map_message(m, &guild_id)).collect())
 }
 
-pub async fn fetch_messages_with_guid(client: &Client, guild_id: String, channel_id: String, before_id: Option<String>) -> Result<Vec<SimpleMessage>, String> {
-     let url = match before_id {
-        Some(before) => format!("{}/channels/{}/messages?limit=50&before={}", API_BASE, channel_id, before),
-        None => format!("{}/channels/{}/messages?limit=50", API_BASE, channel_id),
-    };
+/// 指定メッセージより新しいメッセージを1ページ分取得 (最大100件, 新しい順)
+pub async fn fetch_messages_after(client: &Client, guild_id: String, channel_id: String, after_id: String) -> Result<Vec<SimpleMessage>, AppError> {
+    let url = format!("{}/channels/{}/messages?after={}&limit=100", api_base(), channel_id, after_id);
 
     let res = client.get(&url)
-        .send()
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let messages: Vec<DiscordMessage> = res.json().await.map_err(|e| e.to_string())?;
 
-    Ok(messages.into_iter().map(|m| SimpleMessage {
-        id: m.id,
-        guild_id: guild_id.clone(),
-        channel_id: m.channel_id,
-        content: m.content,
-        author: m.author.username,
-        author_id: m.author.id,
-        timestamp: m.timestamp,
-        embeds: m.embeds,
-        attachments: m.attachments,
-        referenced_message: m.referenced_message.map(|rm| Box::new(SimpleMessage {
-            id: rm.id,
-            guild_id: guild_id.clone(),
-            channel_id: rm.channel_id,
-            content: rm.content,
-            author: rm.author.username,
-            author_id: rm.author.id,
-            timestamp: rm.timestamp,
-            embeds: rm.embeds,
-            attachments: rm.attachments,
-            referenced_message: None,
-            message_snapshots: vec![],
-            kind: map_message_type(rm.kind),
-        })),
-        message_snapshots: m.message_snapshots.unwrap_or_default().into_iter().map(|s| MessageSnapshot {
-            message: SimpleMessageSnapshotData {
-                content: s.message.content,
-                author: s.message.author.map(|a| a.username).unwrap_or_else(|| "Unknown".to_string()),
-                timestamp: s.message.timestamp,
-                embeds: s.message.embeds,
-                attachments: s.message.attachments,
-            }
-        }).collect(),
-        kind: map_message_type(m.kind),
-    }).collect())
+    Ok(messages.into_iter().map(|m| map_message(m, &guild_id)).collect())
+}
+
+/// 指定メッセージの前後を取得 (検索結果からのジャンプ用)
+pub async fn fetch_messages_around(client: &Client, guild_id: String, channel_id: String, message_id: String, limit: u32) -> Result<Vec<SimpleMessage>, AppError> {
+    let url = format!("{}/channels/{}/messages?around={}&limit={}", api_base(), channel_id, message_id, limit.clamp(1, 100));
+
+    let res = client.get(&url)
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    let messages: Vec<DiscordMessage> = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(messages.into_iter().map(|m| map_message(m, &guild_id)).collect())
 }
 
-pub async fn send_message(client: &Client, guild_id: String, channel_id: String, content: String, reply_to: Option<String>) -> Result<SimpleMessage, String> {
+pub async fn send_message(client: &Client, guild_id: String, channel_id: String, content: String, reply_to: Option<String>) -> Result<SimpleMessage, AppError> {
     let mut map = serde_json::Map::new();
     map.insert("content".to_string(), serde_json::Value::String(content));
 
@@ -422,83 +845,230 @@ pub async fn send_message(client: &Client, guild_id: String, channel_id: String,
         map.insert("message_reference".to_string(), serde_json::Value::Object(reference));
     }
 
-    let res = client.post(format!("{}/channels/{}/messages", API_BASE, channel_id))
+    let res = client.post(format!("{}/channels/{}/messages", api_base(), channel_id))
         .json(&map)
-        .send()
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
     }
 
     let m: DiscordMessage = res.json().await.map_err(|e| e.to_string())?;
 
-    Ok(SimpleMessage {
-        id: m.id,
-        guild_id: guild_id.clone(),
-        channel_id: m.channel_id,
-        content: m.content,
-        author: m.author.username,
-        author_id: m.author.id,
-        timestamp: m.timestamp,
-        embeds: m.embeds,
-        attachments: m.attachments,
-        referenced_message: m.referenced_message.map(|rm| Box::new(SimpleMessage {
-            id: rm.id,
-            guild_id: guild_id.clone(),
-            channel_id: rm.channel_id,
-            content: rm.content,
-            author: rm.author.username,
-            author_id: rm.author.id,
-            timestamp: rm.timestamp,
-            embeds: rm.embeds,
-            attachments: rm.attachments,
-            referenced_message: None,
-            message_snapshots: vec![],
-            kind: map_message_type(rm.kind),
-        })),
-        message_snapshots: m.message_snapshots.unwrap_or_default().into_iter().map(|s| MessageSnapshot {
-            message: SimpleMessageSnapshotData {
-                content: s.message.content,
-                author: s.message.author.map(|a| a.username).unwrap_or_else(|| "Unknown".to_string()),
-                timestamp: s.message.timestamp,
-                embeds: s.message.embeds,
-                attachments: s.message.attachments,
-            }
-        }).collect(),
-        kind: map_message_type(m.kind),
-    })
+    Ok(map_message(m, &guild_id))
 }
 
-pub async fn delete_message(client: &Client, channel_id: String, message_id: String) -> Result<(), String> {
-    let res = client.delete(format!("{}/channels/{}/messages/{}", API_BASE, channel_id, message_id))
-        .send()
+/// 埋め込み付きでメッセージを送信
+/// ユーザートークンでは embeds は無視される (Discordの仕様) ため、BOTトークンでログインしている場合のみ使う
+pub async fn send_message_with_embed(client: &Client, guild_id: String, channel_id: String, content: String, embeds: Vec<DiscordEmbed>) -> Result<SimpleMessage, AppError> {
+    // 未設定 (None) の項目は null ではなく省略して送る
+    let embeds: Vec<serde_json::Value> = embeds.iter()
+        .map(|e| strip_nulls(serde_json::to_value(e).unwrap_or_default()))
+        .collect();
+    let body = serde_json::json!({
+        "content": content,
+        "embeds": embeds,
+    });
+
+    let res = client.post(format!("{}/channels/{}/messages", api_base(), channel_id))
+        .json(&body)
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Err(format!("API Error: Status {} - {}", res.status(), res.text().await.unwrap_or_default()));
+        return Err(AppError::from_response(res).await);
+    }
+
+    let m: DiscordMessage = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(map_message(m, &guild_id))
+}
+
+/// JSONオブジェクトから null の項目を再帰的に取り除く
+fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect()
+        ),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(strip_nulls).collect()),
+        other => other,
+    }
+}
+
+pub async fn delete_message(client: &Client, channel_id: String, message_id: String) -> Result<(), AppError> {
+    let res = client.delete(format!("{}/channels/{}/messages/{}", api_base(), channel_id, message_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
     }
 
     Ok(())
 }
 
-pub async fn search_discord(client: &Client, guild_id: String, query: String) -> Result<Vec<SimpleMessage>, String> {
+/// リアクション系エンドポイント用に絵文字をエンコード
+/// Unicode絵文字はそのまま、カスタム絵文字は "name:id" 形式 ("<:name:id>" / "<a:name:id>" も受け付ける)
+fn encode_reaction_emoji(emoji: &str) -> String {
+    let emoji = emoji.trim();
+    let normalized = match emoji.strip_prefix('<').and_then(|e| e.strip_suffix('>')) {
+        Some(inner) => inner.strip_prefix("a:").or_else(|| inner.strip_prefix(':')).unwrap_or(inner),
+        None => emoji,
+    };
+    urlencoding::encode(normalized).into_owned()
+}
+
+/// リアクションしたユーザー一覧の最大取得ページ数 (1ページ100人)
+const REACTION_USERS_MAX_PAGES: usize = 10;
+
+/// 指定した絵文字でリアクションしたユーザーを取得 (after でページング)
+pub async fn fetch_reaction_users(client: &Client, channel_id: String, message_id: String, emoji: String) -> Result<Vec<DiscordUser>, AppError> {
+    let encoded = encode_reaction_emoji(&emoji);
+    let mut users: Vec<DiscordUser> = Vec::new();
+    let mut after: Option<String> = None;
+
+    for _ in 0..REACTION_USERS_MAX_PAGES {
+        let mut url = format!("{}/channels/{}/messages/{}/reactions/{}?limit=100", api_base(), channel_id, message_id, encoded);
+        if let Some(a) = &after {
+            url.push_str(&format!("&after={}", a));
+        }
+
+        let res = client.get(url)
+            .send_throttled(client)
+            .await
+            .map_err(AppError::network)?;
+
+        if !res.status().is_success() {
+            return Err(AppError::from_response(res).await);
+        }
+
+        let page: Vec<DiscordUser> = res.json().await.map_err(|e| e.to_string())?;
+        let page_len = page.len();
+        after = page.last().map(|u| u.id.clone());
+        users.extend(page);
+
+        if page_len < 100 {
+            break;
+        }
+    }
+
+    Ok(users)
+}
+
+/// メッセージのリアクションをすべて削除 (Manage Messages 権限が必要)
+pub async fn remove_all_reactions(client: &Client, channel_id: String, message_id: String) -> Result<(), AppError> {
+    let res = client.delete(format!("{}/channels/{}/messages/{}/reactions", api_base(), channel_id, message_id))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Manage Messages is required"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// 指定した絵文字のリアクションを全ユーザー分削除 (Manage Messages 権限が必要)
+pub async fn remove_reaction_emoji(client: &Client, channel_id: String, message_id: String, emoji: String) -> Result<(), AppError> {
+    let res = client.delete(format!("{}/channels/{}/messages/{}/reactions/{}", api_base(), channel_id, message_id, encode_reaction_emoji(&emoji)))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if res.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(AppError::forbidden("Missing permission: Manage Messages is required"));
+    }
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// メッセージまでを既読としてDiscordに通知 (ack)
+pub async fn ack_message(client: &Client, channel_id: String, message_id: String) -> Result<(), AppError> {
+    let res = client.post(format!("{}/channels/{}/messages/{}/ack", api_base(), channel_id, message_id))
+        .json(&serde_json::json!({ "token": null }))
+        .send_throttled(client)
+        .await
+        .map_err(AppError::network)?;
+
+    if !res.status().is_success() {
+        return Err(AppError::from_response(res).await);
+    }
+
+    Ok(())
+}
+
+/// 検索フィルタ (すべて任意 / contentなしでフィルタのみの検索も可能)
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SearchFilters {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub author_id: Option<String>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub has: Vec<String>,          // "link" | "embed" | "file" | "image" | "video" | "sound" ...
+    #[serde(default)]
+    pub min_id: Option<String>,
+    #[serde(default)]
+    pub max_id: Option<String>,
+    #[serde(default)]
+    pub sort_by: Option<String>,   // "timestamp" | "relevance"
+}
+
+impl SearchFilters {
+    /// クエリ文字列を組み立てる (値があるものだけ)
+    fn to_query_string(&self) -> String {
+        let mut params: Vec<String> = Vec::new();
+        let single = [
+            ("content", &self.content),
+            ("author_id", &self.author_id),
+            ("channel_id", &self.channel_id),
+            ("min_id", &self.min_id),
+            ("max_id", &self.max_id),
+            ("sort_by", &self.sort_by),
+        ];
+        for (key, value) in single {
+            if let Some(v) = value.as_ref().filter(|v| !v.is_empty()) {
+                params.push(format!("{}={}", key, urlencoding::encode(v)));
+            }
+        }
+        for h in &self.has {
+            params.push(format!("has={}", urlencoding::encode(h)));
+        }
+        params.join("&")
+    }
+}
+
+pub async fn search_discord(client: &Client, guild_id: String, filters: SearchFilters) -> Result<SearchResult, AppError> {
     let url = format!(
-        "{}/guilds/{}/messages/search?content={}",
-        API_BASE,
+        "{}/guilds/{}/messages/search?{}",
+        api_base(),
         guild_id,
-        urlencoding::encode(&query)
+        filters.to_query_string()
     );
 
     let res = client.get(&url)
-        .send()
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     if !res.status().is_success() {
-        return Ok(vec![]);
+        return Err(AppError::from_response(res).await);
     }
 
     let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
@@ -509,48 +1079,19 @@ pub async fn search_discord(client: &Client, guild_id: String, query: String) ->
         for msg_wrapper in messages_array {
             if let Some(msg) = msg_wrapper.as_array().and_then(|arr| arr.first()) {
                 if let Ok(m) = serde_json::from_value::<DiscordMessage>(msg.clone()) {
-                    let simple = SimpleMessage {
-                        id: m.id.clone(),
-                        guild_id: guild_id.clone(),
-                        channel_id: m.channel_id.clone(),
-                        content: m.content.clone(),
-                        author: m.author.username.clone(),
-                        author_id: m.author.id.clone(),
-                        timestamp: m.timestamp.clone(),
-                        embeds: m.embeds.clone(),
-                        attachments: m.attachments.clone(),
-                        referenced_message: m.referenced_message.as_ref().map(|rm| Box::new(SimpleMessage {
-                            id: rm.id.clone(),
-                            guild_id: guild_id.clone(),
-                            channel_id: rm.channel_id.clone(),
-                            content: rm.content.clone(),
-                            author: rm.author.username.clone(),
-                            author_id: rm.author.id.clone(),
-                            timestamp: rm.timestamp.clone(),
-                            embeds: rm.embeds.clone(),
-                            attachments: rm.attachments.clone(),
-                            referenced_message: None,
-                            message_snapshots: vec![],
-                            kind: map_message_type(rm.kind),
-                        })),
-                        message_snapshots: m.message_snapshots.unwrap_or_default().into_iter().map(|s| MessageSnapshot {
-                            message: SimpleMessageSnapshotData {
-                                content: s.message.content,
-                                author: s.message.author.map(|a| a.username).unwrap_or_else(|| "Unknown".to_string()),
-                                timestamp: s.message.timestamp,
-                                embeds: s.message.embeds,
-                                attachments: s.message.attachments,
-                            }
-                        }).collect(),
-                        kind: map_message_type(m.kind),
-                    };
+                    let simple = map_message(m, &guild_id);
                     simple_messages.push(simple);
                 }
             }
         }
     }
 
-    Ok(simple_messages)
+    let total_results = body["total_results"].as_u64().unwrap_or(simple_messages.len() as u64) as u32;
+
+    Ok(SearchResult {
+        messages: simple_messages,
+        total_results,
+    })
 }
 
 // =============================
@@ -569,6 +1110,8 @@ pub struct CommandOption {
     #[serde(default)]
     pub choices: Vec<CommandChoice>,
     #[serde(default)]
+    pub autocomplete: bool,          // true なら候補を send_autocomplete_interaction で取得する
+    #[serde(default)]
     pub options: Vec<CommandOption>, // サブコマンド用
 }
 
@@ -610,23 +1153,22 @@ struct ApplicationCommandIndex {
 }
 
 /// ユーザーが使用可能なアプリケーションコマンドを取得
-pub async fn fetch_application_commands(client: &Client, guild_id: Option<String>) -> Result<Vec<ApplicationCommand>, String> {
-    // v9 APIを使用
+pub async fn fetch_application_commands(client: &Client, guild_id: Option<String>) -> Result<Vec<ApplicationCommand>, AppError> {
     // guild_idがある場合はそのギルドのコマンドインデックスを取得
     let url = if let Some(gid) = guild_id {
-        format!("https://discord.com/api/v9/guilds/{}/application-command-index", gid)
+        format!("{}/guilds/{}/application-command-index", api_base(), gid)
     } else {
         // フォールバック: ユーザーコンテキスト（ほぼ使われない？）
-        "https://discord.com/api/v9/users/@me/application-command-index".to_string()
+        format!("{}/users/@me/application-command-index", api_base())
     };
     
     
     println!("[fetch_application_commands] Fetching from: {}", url);
     
     let res = client.get(&url)
-        .send()
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     let status = res.status();
     println!("[fetch_application_commands] Response status: {}", status);
@@ -634,7 +1176,7 @@ pub async fn fetch_application_commands(client: &Client, guild_id: Option<String
     if !status.is_success() {
         let body = res.text().await.unwrap_or_default();
         println!("[fetch_application_commands] Error body: {}", body);
-        return Err(format!("API Error: Status {} - {}", status, body));
+        return Err(AppError::from_status(status, body));
     }
 
     // まず生のテキストを取得してログ出力
@@ -671,6 +1213,10 @@ pub struct InteractionOption {
     pub option_type: u8,
     pub name: String,
     pub value: serde_json::Value,
+    /// オートコンプリート時、入力中のオプションに true を付ける
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focused: Option<bool>,
 }
 
 /// インタラクションデータ
@@ -688,17 +1234,64 @@ pub struct InteractionData {
     pub attachments: Vec<serde_json::Value>,
 }
 
+/// メッセージコンポーネント操作 (ボタン押下・セレクトメニュー選択) のデータ
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ComponentInteractionData {
+    pub component_type: u8,      // 2 = Button, 3 = String Select など
+    pub custom_id: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,     // セレクトメニューで選んだ値
+}
+
+/// モーダル送信のデータ
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ModalSubmitData {
+    pub id: String,              // モーダル表示時に受け取ったID
+    pub custom_id: String,
+    pub components: Vec<serde_json::Value>, // ActionRow > TextInput ({ type: 4, custom_id, value })
+}
+
+/// インタラクション種別ごとの data
+#[derive(Debug, serde::Serialize)]
+#[serde(untagged)]
+enum InteractionPayloadData {
+    Command(InteractionData),
+    Component(ComponentInteractionData),
+    ModalSubmit(ModalSubmitData),
+}
+
 /// インタラクション送信ペイロード
 #[derive(Debug, serde::Serialize)]
 struct InteractionPayload {
     #[serde(rename = "type")]
-    interaction_type: u8,        // 2 = Application Command
+    interaction_type: u8,        // 2 = Application Command, 3 = Message Component, 4 = Autocomplete, 5 = Modal Submit
     application_id: String,
     channel_id: String,
     guild_id: Option<String>,
     session_id: String,
-    data: InteractionData,
+    data: InteractionPayloadData,
     nonce: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<String>,  // Message Component のみ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_flags: Option<u64>,  // Message Component のみ
+}
+
+/// Discordエポック (2015-01-01T00:00:00Z) のUnixミリ秒
+const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// ナンス下位22bit用の連番 (同じミリ秒に複数送信しても重複しないように)
+static NONCE_SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// インタラクションのナンスを生成 (現在時刻 + 連番から作る Snowflake 形式)
+fn generate_nonce() -> String {
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(DISCORD_EPOCH_MS);
+    let sequence = NONCE_SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed) & 0x3F_FFFF;
+    ((now_ms.saturating_sub(DISCORD_EPOCH_MS) << 22) | sequence).to_string()
 }
 
 /// インタラクションを送信（BOTコマンド実行）
@@ -709,36 +1302,118 @@ pub async fn send_interaction(
     application_id: String,
     data: InteractionData,
     session_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     println!("[send_interaction] Called with:");
     println!("  channel_id: {}", channel_id);
     println!("  guild_id: {:?}", guild_id);
     println!("  application_id: {}", application_id);
     println!("  command: {} (id: {})", data.name, data.id);
 
-    // ナンス生成（Snowflake風のID）
-    let nonce = format!("{}", std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0));
-
     let payload = InteractionPayload {
         interaction_type: 2, // Application Command
         application_id,
         channel_id,
         guild_id,
         session_id,
-        data,
-        nonce,
+        data: InteractionPayloadData::Command(data),
+        nonce: generate_nonce(),
+        message_id: None,
+        message_flags: None,
     };
 
-    println!("[send_interaction] Sending payload: {:?}", serde_json::to_string(&payload));
+    post_interaction(client, &payload).await
+}
 
-    let res = client.post("https://discord.com/api/v9/interactions")
-        .json(&payload)
-        .send()
+/// スラッシュコマンドのオートコンプリートを要求 (options のうち入力中のものに focused: true を付ける)
+/// 候補は Gateway の APPLICATION_COMMAND_AUTOCOMPLETE_RESPONSE で届くので、照合用のナンスを返す
+pub async fn send_autocomplete_interaction(
+    client: &Client,
+    channel_id: String,
+    guild_id: Option<String>,
+    application_id: String,
+    data: InteractionData,
+    session_id: String,
+) -> Result<String, AppError> {
+    let nonce = generate_nonce();
+    let payload = InteractionPayload {
+        interaction_type: 4, // Application Command Autocomplete
+        application_id,
+        channel_id,
+        guild_id,
+        session_id,
+        data: InteractionPayloadData::Command(data),
+        nonce: nonce.clone(),
+        message_id: None,
+        message_flags: None,
+    };
+
+    post_interaction(client, &payload).await?;
+    Ok(nonce)
+}
+
+/// ボタン押下・セレクトメニュー選択を送信 (message_id はコンポーネントが付いているメッセージ)
+#[allow(clippy::too_many_arguments)]
+pub async fn send_component_interaction(
+    client: &Client,
+    channel_id: String,
+    guild_id: Option<String>,
+    application_id: String,
+    message_id: String,
+    message_flags: u64,
+    data: ComponentInteractionData,
+    session_id: String,
+) -> Result<(), AppError> {
+    println!("[send_component_interaction] message: {}, custom_id: {}", message_id, data.custom_id);
+
+    let payload = InteractionPayload {
+        interaction_type: 3, // Message Component
+        application_id,
+        channel_id,
+        guild_id,
+        session_id,
+        data: InteractionPayloadData::Component(data),
+        nonce: generate_nonce(),
+        message_id: Some(message_id),
+        message_flags: Some(message_flags),
+    };
+
+    post_interaction(client, &payload).await
+}
+
+/// モーダルの入力内容を送信
+pub async fn send_modal_submit(
+    client: &Client,
+    channel_id: String,
+    guild_id: Option<String>,
+    application_id: String,
+    data: ModalSubmitData,
+    session_id: String,
+) -> Result<(), AppError> {
+    println!("[send_modal_submit] custom_id: {}", data.custom_id);
+
+    let payload = InteractionPayload {
+        interaction_type: 5, // Modal Submit
+        application_id,
+        channel_id,
+        guild_id,
+        session_id,
+        data: InteractionPayloadData::ModalSubmit(data),
+        nonce: generate_nonce(),
+        message_id: None,
+        message_flags: None,
+    };
+
+    post_interaction(client, &payload).await
+}
+
+async fn post_interaction(client: &Client, payload: &InteractionPayload) -> Result<(), AppError> {
+    println!("[send_interaction] Sending payload: {:?}", serde_json::to_string(payload));
+
+    let res = client.post(format!("{}/interactions", api_base()))
+        .json(payload)
+        .send_throttled(client)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::network)?;
 
     let status = res.status();
     println!("[send_interaction] Response status: {}", status);
@@ -746,7 +1421,7 @@ pub async fn send_interaction(
     if !status.is_success() {
         let body = res.text().await.unwrap_or_default();
         println!("[send_interaction] Error body: {}", body);
-        return Err(format!("Interaction Error: Status {} - {}", status, body));
+        return Err(AppError::from_status(status, body));
     }
 
     println!("[send_interaction] Success!");
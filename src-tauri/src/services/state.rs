@@ -1,8 +1,25 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+/// REST リクエストの同時実行数の上限
+/// ギルドを開いたときのチャンネル・スレッド・ロール等の一斉取得でグローバルレート制限 (429) に当たらないよう平準化する
+pub const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// REST が 401 (トークン失効・取り消し) を返したときの処理
+pub type UnauthorizedHandler = Arc<dyn Fn() + Send + Sync>;
 
 pub struct DiscordState {
-    pub client: Arc<Mutex<Option<reqwest::Client>>>,
+    pub client: Arc<Mutex<Option<ApiClient>>>,
+    // ログイン中のアカウントがBOTか (BOTトークンでのみ使えるAPIの判定用)
+    pub is_bot: AtomicBool,
+    // クライアントを作り直すたびに増える世代 (401 の確認中に再ログインされたかの判定用)
+    pub client_generation: AtomicU64,
+    // REST の同時実行数制限 (再ログイン後のクライアントも同じものを使う)
+    pub request_limiter: Arc<Semaphore>,
+    // 401 を受けたときの処理 (init_auth_expiry_watch で登録)
+    pub unauthorized_handler: Arc<Mutex<Option<UnauthorizedHandler>>>,
 }
 
 
@@ -11,6 +28,74 @@ impl DiscordState {
     pub fn new() -> Self {
         Self {
             client: Arc::new(Mutex::new(None)),
+            is_bot: AtomicBool::new(false),
+            client_generation: AtomicU64::new(0),
+            request_limiter: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+            unauthorized_handler: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn set_bot(&self, is_bot: bool) {
+        self.is_bot.store(is_bot, Ordering::Relaxed);
+    }
+
+    pub fn is_bot(&self) -> bool {
+        self.is_bot.load(Ordering::Relaxed)
+    }
+
+    /// ログインで作った HTTP クライアントに、このステートの同時実行数制限と 401 の処理を紐付ける
+    pub fn api_client(&self, http: reqwest::Client) -> ApiClient {
+        ApiClient {
+            http,
+            limiter: self.request_limiter.clone(),
+            unauthorized_handler: self.unauthorized_handler.clone(),
+        }
+    }
+
+    /// 401 を受けたときの処理を登録 (既存の登録は置き換える)
+    pub fn set_unauthorized_handler(&self, handler: impl Fn() + Send + Sync + 'static) {
+        if let Ok(mut h) = self.unauthorized_handler.lock() {
+            *h = Some(Arc::new(handler));
+        }
+    }
+}
+
+/// REST 呼び出し用のクライアント
+/// reqwest::Client としてそのまま使え、送信は send_throttled で DiscordState の制限を通す
+#[derive(Clone)]
+pub struct ApiClient {
+    http: reqwest::Client,
+    limiter: Arc<Semaphore>,
+    unauthorized_handler: Arc<Mutex<Option<UnauthorizedHandler>>>,
+}
+
+impl Deref for ApiClient {
+    type Target = reqwest::Client;
+
+    fn deref(&self) -> &reqwest::Client {
+        &self.http
+    }
+}
+
+impl ApiClient {
+    /// 同時実行数制限を通してリクエストを送信し、401 なら登録済みの処理に通知する
+    pub async fn send_throttled(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, reqwest::Error> {
+        // セマフォは close しないので acquire は失敗しない
+        let _permit = self.limiter.acquire().await.ok();
+        let res = request.send().await;
+        if let Ok(res) = &res {
+            if res.status() == reqwest::StatusCode::UNAUTHORIZED {
+                self.notify_unauthorized();
+            }
+        }
+        res
+    }
+
+    fn notify_unauthorized(&self) {
+        // ロックを持ったまま呼ばないよう、取り出してから実行する
+        let handler = self.unauthorized_handler.lock().ok().and_then(|h| h.clone());
+        if let Some(handler) = handler {
+            handler();
         }
     }
 }
@@ -0,0 +1,92 @@
+// attachments - 添付ファイルのディスクキャッシュ
+// Discord CDNのURLは期限切れになるため、一度見たメディアはローカルに保存して再利用する
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+
+/// 添付ファイルキャッシュのディレクトリ (app_data_dir/attachments)
+pub fn attachment_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("attachments");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// ファイル名として安全な文字列に変換 (パス区切り等を除去)
+pub(crate) fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// 一時ファイル名の連番 (同じファイルを同時にダウンロードしても書き込み先が衝突しないように)
+static DOWNLOAD_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// URLをダウンロードしてファイルに保存 (アバター等のキャッシュと共用)
+pub(crate) async fn download_to_file(url: &str, path: &Path) -> Result<(), String> {
+    // CDNは認証不要なので、Discordクライアント (Authorizationヘッダー付き) は使わない
+    let res = reqwest::get(url).await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Download failed: Status {}", res.status()));
+    }
+
+    // 途中で失敗したファイルがキャッシュとして残らないよう、ダウンロードごとの一時ファイルに書いてからリネーム
+    let tmp_path = PathBuf::from(format!(
+        "{}.{}-{}.part",
+        path.to_string_lossy(),
+        std::process::id(),
+        DOWNLOAD_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = match write_response(res, &tmp_path).await {
+        Ok(()) => tokio::fs::rename(&tmp_path, path).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+    }
+
+    result
+}
+
+async fn write_response(mut res: reqwest::Response, tmp_path: &Path) -> Result<(), String> {
+    let mut file = tokio::fs::File::create(tmp_path).await.map_err(|e| e.to_string())?;
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+    }
+    file.flush().await.map_err(|e| e.to_string())
+}
+
+/// 添付ファイルをダウンロードしてキャッシュし、ローカルパスを返す
+/// キャッシュ済み (message_id + filename) の場合はダウンロードしない
+#[tauri::command]
+pub async fn download_attachment(
+    app: AppHandle,
+    url: String,
+    message_id: String,
+    filename: String,
+) -> Result<String, String> {
+    let dir = attachment_cache_dir(&app)?;
+    let path = dir.join(format!("{}_{}", sanitize_filename(&message_id), sanitize_filename(&filename)));
+
+    if path.exists() {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    download_to_file(&url, &path).await?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 添付ファイルキャッシュを全削除
+#[tauri::command]
+pub async fn clear_attachment_cache(app: AppHandle) -> Result<(), String> {
+    let dir = attachment_cache_dir(&app)?;
+    tokio::fs::remove_dir_all(&dir).await.map_err(|e| e.to_string())?;
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+// avatars - アバター・ギルドアイコンのディスクキャッシュ
+// 同じ画像をCDNから何度も取得しないよう、一度取得したものはローカルに保存して再利用する (オフライン表示にも使う)
+
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use super::attachments::{download_to_file, sanitize_filename};
+use crate::services::config::cdn_asset_url;
+
+/// 既定の画像サイズ (Discordクライアントのアイコン表示と同じ)
+const DEFAULT_SIZE: u32 = 128;
+
+/// アバターキャッシュのディレクトリ (app_data_dir/avatars)
+pub fn avatar_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("avatars");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// CDNアセットをキャッシュから取得し、無ければダウンロードしてローカルパスを返す
+/// ハッシュが変われば別ファイルになるので、古い画像が表示され続けることはない
+async fn get_cached_asset(app: &AppHandle, base: &str, id: &str, hash: &str, size: Option<u32>) -> Result<String, String> {
+    let size = size.unwrap_or(DEFAULT_SIZE);
+    // CDNが受け付けるのは 16〜4096 の2のべき乗のみ (任意の値でキャッシュファイルが増えるのも防ぐ)
+    if !(16..=4096).contains(&size) || !size.is_power_of_two() {
+        return Err(format!("Invalid size {}: must be a power of two between 16 and 4096", size));
+    }
+    let url = format!("{}?size={}", cdn_asset_url(base, id, hash), size);
+    // アニメーション (a_) は .gif、それ以外は .png (cdn_asset_url と同じ判定)
+    let ext = if hash.starts_with("a_") { "gif" } else { "png" };
+
+    let dir = avatar_cache_dir(app)?;
+    let path = dir.join(format!(
+        "{}_{}_{}_{}.{}",
+        base, sanitize_filename(id), sanitize_filename(hash), size, ext
+    ));
+
+    if path.exists() {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    download_to_file(&url, &path).await?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// ユーザーのアバターを取得 (キャッシュ済みならダウンロードしない)
+#[tauri::command]
+pub async fn get_avatar(
+    app: AppHandle,
+    user_id: String,
+    hash: String,
+    size: Option<u32>,
+) -> Result<String, String> {
+    get_cached_asset(&app, "avatars", &user_id, &hash, size).await
+}
+
+/// ギルドアイコンを取得 (キャッシュ済みならダウンロードしない)
+#[tauri::command]
+pub async fn get_guild_icon(
+    app: AppHandle,
+    guild_id: String,
+    hash: String,
+    size: Option<u32>,
+) -> Result<String, String> {
+    get_cached_asset(&app, "icons", &guild_id, &hash, size).await
+}
+
+/// アバター・アイコンのキャッシュを全削除
+#[tauri::command]
+pub async fn clear_avatar_cache(app: AppHandle) -> Result<(), String> {
+    let dir = avatar_cache_dir(&app)?;
+    tokio::fs::remove_dir_all(&dir).await.map_err(|e| e.to_string())?;
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// channel_settings - チャンネルごとのローカル設定 (ミュート等)
+// Discord側の通知設定とは独立に保持し、ミュート中のチャンネルはGatewayからの通知を抑制する
+
+use rusqlite::{Connection, OptionalExtension, params};
+use tauri::State;
+
+use super::DatabaseState;
+
+/// チャンネルのローカル設定
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ChannelSettings {
+    pub channel_id: String,
+    pub muted: bool,
+}
+
+/// channel_settings テーブルを作成 (migrations から呼ぶ)
+pub fn init_channel_settings_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS channel_settings (
+            channel_id TEXT PRIMARY KEY,
+            muted INTEGER NOT NULL DEFAULT 0
+        );
+        "
+    ).map_err(|e| e.to_string())
+}
+
+/// チャンネルがミュートされているか (設定が無ければ false)
+pub fn is_channel_muted(conn: &Connection, channel_id: &str) -> bool {
+    conn.query_row(
+        "SELECT muted FROM channel_settings WHERE channel_id = ?1",
+        params![channel_id],
+        |row| row.get::<_, bool>(0),
+    ).optional().ok().flatten().unwrap_or(false)
+}
+
+// チャンネルのミュートを設定
+#[tauri::command]
+pub fn set_channel_muted(
+    channel_id: String,
+    muted: bool,
+    state: State<'_, DatabaseState>,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO channel_settings (channel_id, muted) VALUES (?1, ?2)
+         ON CONFLICT(channel_id) DO UPDATE SET muted = excluded.muted",
+        params![channel_id, muted],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 保存済みのチャンネル設定を全件取得
+#[tauri::command]
+pub fn get_channel_settings(state: State<'_, DatabaseState>) -> Result<Vec<ChannelSettings>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT channel_id, muted FROM channel_settings")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ChannelSettings {
+            channel_id: row.get(0)?,
+            muted: row.get(1)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
@@ -0,0 +1,80 @@
+// message_cache - get_cached_messages 用のインメモリLRU
+// 少数のチャンネルを行き来するときに毎回SQLiteを読まないよう、最近開いたチャンネルの取得結果を保持する
+// DBへの書き込み・削除時は該当チャンネル (または全体) を破棄する
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use crate::services::models::SimpleMessage;
+
+/// 保持するチャンネル数の上限
+const MESSAGE_CACHE_CHANNELS: usize = 8;
+/// 1チャンネルあたりに保持するページ数の上限 (スクロールで増えすぎないように)
+const MESSAGE_CACHE_PAGES_PER_CHANNEL: usize = 16;
+
+/// ページのキー (before_id, after_id, limit)
+type PageKey = (Option<String>, Option<String>, u32);
+
+#[derive(Default)]
+struct MessageCache {
+    // channel_id -> { ページ -> メッセージ }
+    pages: HashMap<String, HashMap<PageKey, Vec<SimpleMessage>>>,
+    // 最近使ったチャンネル順 (先頭が最も古い)
+    order: VecDeque<String>,
+}
+
+impl MessageCache {
+    fn touch(&mut self, channel_id: &str) {
+        if let Some(pos) = self.order.iter().position(|c| c == channel_id) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(channel_id.to_string());
+    }
+}
+
+fn cache() -> &'static Mutex<MessageCache> {
+    static CACHE: OnceLock<Mutex<MessageCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(MessageCache::default()))
+}
+
+/// キャッシュ済みのページを取得
+pub fn get(channel_id: &str, key: &PageKey) -> Option<Vec<SimpleMessage>> {
+    let mut cache = cache().lock().ok()?;
+    let messages = cache.pages.get(channel_id)?.get(key)?.clone();
+    cache.touch(channel_id);
+    Some(messages)
+}
+
+/// ページを保存 (上限を超えたら最も古いチャンネルを捨てる)
+pub fn put(channel_id: &str, key: PageKey, messages: Vec<SimpleMessage>) {
+    let Ok(mut cache) = cache().lock() else { return };
+    let pages = cache.pages.entry(channel_id.to_string()).or_default();
+    if pages.len() >= MESSAGE_CACHE_PAGES_PER_CHANNEL && !pages.contains_key(&key) {
+        pages.clear();
+    }
+    pages.insert(key, messages);
+    cache.touch(channel_id);
+
+    while cache.order.len() > MESSAGE_CACHE_CHANNELS {
+        if let Some(oldest) = cache.order.pop_front() {
+            cache.pages.remove(&oldest);
+        }
+    }
+}
+
+/// チャンネルのキャッシュを破棄 (メッセージ保存時)
+pub fn invalidate_channel(channel_id: &str) {
+    let Ok(mut cache) = cache().lock() else { return };
+    cache.pages.remove(channel_id);
+    if let Some(pos) = cache.order.iter().position(|c| c == channel_id) {
+        cache.order.remove(pos);
+    }
+}
+
+/// すべてのキャッシュを破棄 (ギルド単位の削除・古いメッセージの整理時)
+pub fn clear() {
+    if let Ok(mut cache) = cache().lock() {
+        cache.pages.clear();
+        cache.order.clear();
+    }
+}
@@ -0,0 +1,143 @@
+// migrations - スキーマのバージョン管理
+// 変更はすべて番号付きのマイグレーションとして追加し、schema_version に適用済みの番号を記録する
+// (以前の ALTER TABLE をエラー無視で毎回流す方式は廃止)
+
+use rusqlite::{Connection, params};
+
+use super::{channel_settings, read_states};
+
+/// マイグレーション1件 (version は 1 から連番)
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Connection) -> Result<(), String>,
+}
+
+/// 適用順に並べたマイグレーション一覧。既存の項目は変更せず、末尾に追加すること
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "create messages table", apply: create_messages_table },
+    Migration { version: 2, description: "add messages.guild_id / author_id", apply: add_guild_and_author_columns },
+    Migration { version: 3, description: "add messages.reactions", apply: add_reactions_column },
+    Migration { version: 4, description: "add messages.components", apply: add_components_column },
+    Migration { version: 5, description: "create message indexes", apply: create_message_indexes },
+    Migration { version: 6, description: "create read_states / channel_settings", apply: create_settings_tables },
+    Migration { version: 7, description: "create messages_fts", apply: create_fts_table },
+    Migration { version: 8, description: "add author to messages_fts and backfill", apply: rebuild_fts_with_author },
+];
+
+/// 未適用のマイグレーションを順に適用する (DatabaseState::open から呼ぶ)
+pub fn run_migrations(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+        "
+    ).map_err(|e| e.to_string())?;
+
+    let current = current_version(conn)?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        // 1件ずつトランザクションで適用し、途中で失敗しても中途半端な状態を残さない
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        (migration.apply)(&tx)
+            .map_err(|e| format!("Migration {} ({}) failed: {}", migration.version, migration.description, e))?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description) VALUES (?1, ?2)",
+            params![migration.version, migration.description],
+        ).map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+        println!("[Store] Applied migration {}: {}", migration.version, migration.description);
+    }
+
+    Ok(())
+}
+
+/// 適用済みの最新バージョン (未適用なら 0)
+pub fn current_version(conn: &Connection) -> Result<u32, String> {
+    conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// カラムが無い場合のみ追加 (バージョン管理導入前のDBには既に存在することがある)
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), String> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).map_err(|e| e.to_string())?;
+    let exists = stmt.query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn create_messages_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS messages (
+            id TEXT PRIMARY KEY,
+            guild_id TEXT NOT NULL DEFAULT '',
+            channel_id TEXT NOT NULL,
+            content TEXT,
+            author TEXT,
+            timestamp TEXT,
+            embeds TEXT,
+            attachments TEXT,
+            attachment_filenames TEXT
+        );
+        "
+    ).map_err(|e| e.to_string())
+}
+
+fn add_guild_and_author_columns(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "messages", "guild_id", "TEXT NOT NULL DEFAULT ''")?;
+    add_column_if_missing(conn, "messages", "author_id", "TEXT NOT NULL DEFAULT ''")
+}
+
+fn add_reactions_column(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "messages", "reactions", "TEXT NOT NULL DEFAULT '[]'")
+}
+
+fn add_components_column(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "messages", "components", "TEXT NOT NULL DEFAULT '[]'")
+}
+
+fn create_message_indexes(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "
+        CREATE INDEX IF NOT EXISTS idx_channel ON messages(channel_id);
+        CREATE INDEX IF NOT EXISTS idx_guild ON messages(guild_id);
+        CREATE INDEX IF NOT EXISTS idx_author ON messages(author_id);
+        CREATE INDEX IF NOT EXISTS idx_timestamp ON messages(channel_id, timestamp DESC);
+        "
+    ).map_err(|e| e.to_string())
+}
+
+fn create_settings_tables(conn: &Connection) -> Result<(), String> {
+    read_states::init_read_states_table(conn)?;
+    channel_settings::init_channel_settings_table(conn)
+}
+
+fn create_fts_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(id, content, attachment_filenames, tokenize='unicode61');"
+    ).map_err(|e| format!("FTS create error: {}", e))
+}
+
+/// 投稿者名でも検索できるよう、FTSテーブルに author を追加して既存メッセージから作り直す
+/// (FTS5 は ALTER TABLE でカラムを追加できない)
+fn rebuild_fts_with_author(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "
+        DROP TABLE IF EXISTS messages_fts;
+        CREATE VIRTUAL TABLE messages_fts USING fts5(id, content, author, attachment_filenames, tokenize='unicode61');
+        INSERT INTO messages_fts (id, content, author, attachment_filenames)
+            SELECT id, content, author, attachment_filenames FROM messages;
+        "
+    ).map_err(|e| format!("FTS rebuild error: {}", e))
+}
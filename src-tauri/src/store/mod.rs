@@ -1,70 +1,144 @@
-// database.rs - SQLiteによるメッセージ永続化と検索
+// store - SQLiteによるメッセージ永続化と検索
 
-use rusqlite::{Connection, params};
+pub mod attachments;
+pub mod avatars;
+pub mod read_states;
+pub mod channel_settings;
+mod message_cache;
+mod migrations;
+
+use rusqlite::{Connection, OptionalExtension, params};
 use std::sync::{Arc, Mutex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
 
-use crate::services::models::{SimpleMessage, DiscordEmbed, DiscordAttachment};
+use crate::services::models::SimpleMessage;
 
 pub struct DatabaseState {
     pub conn: Arc<Mutex<Connection>>,
+    pub db_path: PathBuf,
 }
 
 impl DatabaseState {
     pub fn new(app_data_dir: PathBuf) -> Result<Self, String> {
         std::fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
         let db_path = app_data_dir.join("messages.db");
-        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-        
-        // テーブル作成 (新規DB用)
-        conn.execute_batch(
-            "
-            CREATE TABLE IF NOT EXISTS messages (
-                id TEXT PRIMARY KEY,
-                guild_id TEXT NOT NULL DEFAULT '',
-                channel_id TEXT NOT NULL,
-                content TEXT,
-                author TEXT,
-                timestamp TEXT,
-                embeds TEXT,
-                attachments TEXT,
-                attachment_filenames TEXT
-            );
-            "
-        ).map_err(|e| e.to_string())?;
-        
-        // 既存DBのマイグレーション: guild_id, author_id カラムが存在しない場合に追加
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN guild_id TEXT NOT NULL DEFAULT ''", []);
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN author_id TEXT NOT NULL DEFAULT ''", []);
-        
-        // インデックス作成 (マイグレーション後に実行)
-        conn.execute_batch(
-            "
-            CREATE INDEX IF NOT EXISTS idx_channel ON messages(channel_id);
-            CREATE INDEX IF NOT EXISTS idx_guild ON messages(guild_id);
-            CREATE INDEX IF NOT EXISTS idx_author ON messages(author_id);
-            CREATE INDEX IF NOT EXISTS idx_timestamp ON messages(channel_id, timestamp DESC);
-            "
-        ).map_err(|e| e.to_string())?;
 
-        // FTS5テーブル作成 (存在しない場合のみ)
-        let fts_exists: bool = conn.query_row(
-            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='messages_fts'",
-            [],
-            |row| row.get(0)
-        ).unwrap_or(false);
-
-        if !fts_exists {
-            conn.execute_batch(
-                "CREATE VIRTUAL TABLE messages_fts USING fts5(id, content, attachment_filenames, tokenize='unicode61');"
-            ).map_err(|e| format!("FTS create error: {}", e))?;
-        }
+        // 開けない場合はDBファイルの破損を疑い、破損していればバックアップして作り直す
+        // (キャッシュを失うより起動時にクラッシュする方が困る)
+        let conn = match Self::open(&db_path) {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("[Store] Failed to open database: {}", e);
+                if !is_corrupt_db(&db_path) {
+                    // 破損ではない (権限・ディスク容量等) ので作り直さない
+                    return Err(e);
+                }
+                let backup_path = backup_corrupt_db(&db_path)?;
+                eprintln!("[Store] Moved corrupt database to {:?}, recreating", backup_path);
+                Self::open(&db_path)?
+            }
+        };
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            db_path,
         })
     }
+
+    /// ディスク上のDBを使えないときの代替 (メモリ上のDB。終了すると消える)
+    pub fn in_memory(db_path: PathBuf) -> Result<Self, String> {
+        let mut conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        migrations::run_migrations(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            db_path,
+        })
+    }
+
+    /// DBを開いてテーブル作成・マイグレーションを行う
+    fn open(db_path: &Path) -> Result<Connection, String> {
+        let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+        migrations::run_migrations(&mut conn)?;
+        Ok(conn)
+    }
+
+    /// アプリ終了時の後処理 (WALのチェックポイントと統計の最適化)
+    pub fn shutdown(&self) {
+        if let Ok(conn) = self.conn.lock() {
+            if let Err(e) = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE); PRAGMA optimize;") {
+                eprintln!("[Store] Shutdown checkpoint failed: {}", e);
+            }
+        }
+    }
+}
+
+/// PRAGMA integrity_check を実行し、問題の一覧を返す (正常なら空)
+fn run_integrity_check(conn: &Connection) -> Result<Vec<String>, String> {
+    integrity_errors(conn).map_err(|e| e.to_string())
+}
+
+fn integrity_errors(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let results = rows.collect::<Result<Vec<_>, _>>()?;
+    Ok(results.into_iter().filter(|r| r != "ok").collect())
+}
+
+/// 開けなかったDBファイルが破損しているか (起動時の復旧判定用)
+/// 整合性チェックで問題が見つかったか、SQLITE_CORRUPT / SQLITE_NOTADB で読めない場合のみ破損とみなす
+fn is_corrupt_db(db_path: &Path) -> bool {
+    match Connection::open(db_path).and_then(|conn| integrity_errors(&conn)) {
+        Ok(errors) if errors.is_empty() => false,
+        Ok(errors) => {
+            eprintln!("[Store] Integrity check failed: {}", errors.join("; "));
+            true
+        }
+        Err(e) => {
+            eprintln!("[Store] Integrity check could not run: {}", e);
+            matches!(
+                e.sqlite_error_code(),
+                Some(rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+            )
+        }
+    }
+}
+
+/// 破損したDBファイル (と WAL/SHM) を messages.db.corrupt-<UNIX時刻> に退避
+fn backup_corrupt_db(db_path: &Path) -> Result<PathBuf, String> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let backup_path = PathBuf::from(format!("{}.corrupt-{}", db_path.to_string_lossy(), timestamp));
+    std::fs::rename(db_path, &backup_path).map_err(|e| format!("Failed to back up corrupt database: {}", e))?;
+
+    // 古い WAL/SHM が残っていると新しいDBに適用されてしまうので一緒に退避する
+    for suffix in ["-wal", "-shm"] {
+        let path = PathBuf::from(format!("{}{}", db_path.to_string_lossy(), suffix));
+        if path.exists() {
+            let _ = std::fs::rename(&path, format!("{}{}", backup_path.to_string_lossy(), suffix));
+        }
+    }
+    Ok(backup_path)
+}
+
+/// 整合性チェックの結果
+#[derive(serde::Serialize)]
+pub struct IntegrityCheckResult {
+    pub ok: bool,
+    pub errors: Vec<String>,
+}
+
+// DBの整合性チェック
+#[tauri::command]
+pub fn check_db_integrity(state: State<'_, DatabaseState>) -> Result<IntegrityCheckResult, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let errors = run_integrity_check(&conn)?;
+    Ok(IntegrityCheckResult {
+        ok: errors.is_empty(),
+        errors,
+    })
 }
 
 // メッセージを保存
@@ -77,10 +151,12 @@ pub fn save_message(conn: &Connection, msg: &SimpleMessage) -> Result<(), String
 
     let embeds_json = serde_json::to_string(&msg.embeds).unwrap_or_default();
     let attachments_json = serde_json::to_string(&msg.attachments).unwrap_or_default();
+    let reactions_json = serde_json::to_string(&msg.reactions).unwrap_or_default();
+    let components_json = serde_json::to_string(&msg.components).unwrap_or_default();
 
     conn.execute(
-        "INSERT OR REPLACE INTO messages (id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, attachment_filenames)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        "INSERT OR REPLACE INTO messages (id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, attachment_filenames, reactions, components)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
         params![
             msg.id,
             msg.guild_id,
@@ -92,8 +168,11 @@ pub fn save_message(conn: &Connection, msg: &SimpleMessage) -> Result<(), String
             embeds_json,
             attachments_json,
             attachment_filenames,
+            reactions_json,
+            components_json,
         ],
     ).map_err(|e| e.to_string())?;
+    message_cache::invalidate_channel(&msg.channel_id);
 
     // FTS更新 (重複防止のため先に削除)
     conn.execute(
@@ -102,8 +181,8 @@ pub fn save_message(conn: &Connection, msg: &SimpleMessage) -> Result<(), String
     ).ok(); // エラーは無視
     
     conn.execute(
-        "INSERT INTO messages_fts (id, content, attachment_filenames) VALUES (?1, ?2, ?3)",
-        params![msg.id, msg.content, attachment_filenames],
+        "INSERT INTO messages_fts (id, content, author, attachment_filenames) VALUES (?1, ?2, ?3, ?4)",
+        params![msg.id, msg.content, msg.author, attachment_filenames],
     ).map_err(|e| e.to_string())?;
 
     Ok(())
@@ -122,18 +201,42 @@ pub fn save_messages(conn: &Connection, messages: &[SimpleMessage]) -> Result<()
 pub fn get_cached_messages(
     channel_id: String,
     before_id: Option<String>,
+    after_id: Option<String>,
     limit: Option<u32>,
     state: State<'_, DatabaseState>,
 ) -> Result<Vec<SimpleMessage>, String> {
+    if before_id.is_some() && after_id.is_some() {
+        return Err("before_id and after_id cannot be specified together".to_string());
+    }
+
+    let limit = limit.unwrap_or(50);
+    let cache_key = (before_id.clone(), after_id.clone(), limit);
+    if let Some(messages) = message_cache::get(&channel_id, &cache_key) {
+        return Ok(messages);
+    }
+
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
-    let limit = limit.unwrap_or(50) as i64;
+    let limit = limit as i64;
 
     let mut messages = Vec::new();
 
-    // before_idがある場合とない場合で別々にクエリ実行
-    if let Some(before) = &before_id {
-            let mut stmt = conn.prepare(
-            "SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments 
+    // before_id / after_id / 指定なし で別々にクエリ実行
+    if let Some(after) = &after_id {
+        // 前方 (新しい方向) へのページング: 古い順で返す
+        let mut stmt = conn.prepare(
+            "SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, reactions, components
+             FROM messages 
+             WHERE channel_id = ?1 AND timestamp > (SELECT timestamp FROM messages WHERE id = ?2)
+             ORDER BY timestamp ASC LIMIT ?3"
+        ).map_err(|e| e.to_string())?;
+        
+        let mut rows = stmt.query(params![channel_id, after, limit]).map_err(|e| e.to_string())?;
+        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            messages.push(row_to_message(row)?);
+        }
+    } else if let Some(before) = &before_id {
+        let mut stmt = conn.prepare(
+            "SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, reactions, components
              FROM messages 
              WHERE channel_id = ?1 AND timestamp < (SELECT timestamp FROM messages WHERE id = ?2)
              ORDER BY timestamp DESC LIMIT ?3"
@@ -141,30 +244,11 @@ pub fn get_cached_messages(
         
         let mut rows = stmt.query(params![channel_id, before, limit]).map_err(|e| e.to_string())?;
         while let Some(row) = rows.next().map_err(|e| e.to_string())? {
-            let id: String = row.get(0).map_err(|e| e.to_string())?;
-            let g_id: String = row.get(1).map_err(|e| e.to_string())?;
-            let ch_id: String = row.get(2).map_err(|e| e.to_string())?;
-            let content: String = row.get(3).map_err(|e| e.to_string())?;
-            let author: String = row.get(4).map_err(|e| e.to_string())?;
-            // Handle older DBs where author_id might be missing/null (though migration adds it) where default is empty
-            let author_id: String = row.get(5).unwrap_or_default(); 
-            let timestamp: String = row.get(6).map_err(|e| e.to_string())?;
-            let embeds_json: String = row.get(7).map_err(|e| e.to_string())?;
-            let attachments_json: String = row.get(8).map_err(|e| e.to_string())?;
-            
-            let embeds: Vec<DiscordEmbed> = serde_json::from_str(&embeds_json).unwrap_or_default();
-            let attachments: Vec<DiscordAttachment> = serde_json::from_str(&attachments_json).unwrap_or_default();
-            
-            messages.push(SimpleMessage {
-                id, guild_id: g_id, channel_id: ch_id, content, author, author_id, timestamp, embeds, attachments,
-                referenced_message: None,
-                message_snapshots: vec![],
-                kind: "Default".to_string(),
-            });
+            messages.push(row_to_message(row)?);
         }
     } else {
         let mut stmt = conn.prepare(
-            "SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments 
+            "SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, reactions, components
              FROM messages 
              WHERE channel_id = ?1
              ORDER BY timestamp DESC LIMIT ?2"
@@ -172,31 +256,23 @@ pub fn get_cached_messages(
         
         let mut rows = stmt.query(params![channel_id, limit]).map_err(|e| e.to_string())?;
         while let Some(row) = rows.next().map_err(|e| e.to_string())? {
-            let id: String = row.get(0).map_err(|e| e.to_string())?;
-            let g_id: String = row.get(1).map_err(|e| e.to_string())?;
-            let ch_id: String = row.get(2).map_err(|e| e.to_string())?;
-            let content: String = row.get(3).map_err(|e| e.to_string())?;
-            let author: String = row.get(4).map_err(|e| e.to_string())?;
-            let author_id: String = row.get(5).unwrap_or_default();
-            let timestamp: String = row.get(6).map_err(|e| e.to_string())?;
-            let embeds_json: String = row.get(7).map_err(|e| e.to_string())?;
-            let attachments_json: String = row.get(8).map_err(|e| e.to_string())?;
-            
-            let embeds: Vec<DiscordEmbed> = serde_json::from_str(&embeds_json).unwrap_or_default();
-            let attachments: Vec<DiscordAttachment> = serde_json::from_str(&attachments_json).unwrap_or_default();
-            
-            messages.push(SimpleMessage {
-                id, guild_id: g_id, channel_id: ch_id, content, author, author_id, timestamp, embeds, attachments,
-                referenced_message: None,
-                message_snapshots: vec![],
-                kind: "Default".to_string(),
-            });
+            messages.push(row_to_message(row)?);
         }
     }
 
+    message_cache::put(&channel_id, cache_key, messages.clone());
     Ok(messages)
 }
 
+// チャンネルのキャッシュ済みメッセージのうち最新のIDを取得
+pub fn newest_cached_message_id(conn: &Connection, channel_id: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT id FROM messages WHERE channel_id = ?1 ORDER BY timestamp DESC LIMIT 1",
+        params![channel_id],
+        |row| row.get(0),
+    ).optional().map_err(|e| e.to_string())
+}
+
 // メッセージ検索 (FTS5) - サーバー全体検索
 #[tauri::command]
 pub fn search_messages(
@@ -205,10 +281,14 @@ pub fn search_messages(
     state: State<'_, DatabaseState>,
 ) -> Result<Vec<SimpleMessage>, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    search_messages_local(&conn, &guild_id, &query)
+}
 
+// FTS5によるローカル検索 (コマンド/ハイブリッド検索の共通処理)
+pub fn search_messages_local(conn: &Connection, guild_id: &str, query: &str) -> Result<Vec<SimpleMessage>, String> {
     // FTSで検索し、guild_idでフィルタ (サーバー全体)
     let sql = "
-        SELECT m.id, m.guild_id, m.channel_id, m.content, m.author, m.author_id, m.timestamp, m.embeds, m.attachments
+        SELECT m.id, m.guild_id, m.channel_id, m.content, m.author, m.author_id, m.timestamp, m.embeds, m.attachments, m.reactions, m.components
         FROM messages_fts fts
         JOIN messages m ON fts.id = m.id
         WHERE messages_fts MATCH ?1 AND m.guild_id = ?2
@@ -223,34 +303,216 @@ pub fn search_messages(
 
     let mut messages = Vec::new();
     while let Some(row) = rows.next().map_err(|e| e.to_string())? {
-        let id: String = row.get(0).map_err(|e| e.to_string())?;
-        let g_id: String = row.get(1).map_err(|e| e.to_string())?;
-        let ch_id: String = row.get(2).map_err(|e| e.to_string())?;
-        let content: String = row.get(3).map_err(|e| e.to_string())?;
-        let author: String = row.get(4).map_err(|e| e.to_string())?;
-        let author_id: String = row.get(5).unwrap_or_default();
-        let timestamp: String = row.get(6).map_err(|e| e.to_string())?;
-        let embeds_json: String = row.get(7).map_err(|e| e.to_string())?;
-        let attachments_json: String = row.get(8).map_err(|e| e.to_string())?;
-
-        let embeds: Vec<DiscordEmbed> = serde_json::from_str(&embeds_json).unwrap_or_default();
-        let attachments: Vec<DiscordAttachment> = serde_json::from_str(&attachments_json).unwrap_or_default();
-        
-        messages.push(SimpleMessage {
-            id,
-            guild_id: g_id,
-            channel_id: ch_id,
-            content,
-            author,
-            author_id,
-            timestamp,
-            embeds,
-            attachments,
-            referenced_message: None,
-            message_snapshots: vec![],
-            kind: "Default".to_string(),
-        });
+        messages.push(row_to_message(row)?);
     }
 
     Ok(messages)
 }
+
+// SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, reactions, components の行をSimpleMessageに変換
+fn row_to_message(row: &rusqlite::Row) -> Result<SimpleMessage, String> {
+    let embeds_json: String = row.get(7).map_err(|e| e.to_string())?;
+    let attachments_json: String = row.get(8).map_err(|e| e.to_string())?;
+    let reactions_json: String = row.get(9).unwrap_or_default();
+    let components_json: String = row.get(10).unwrap_or_default();
+
+    Ok(SimpleMessage {
+        id: row.get(0).map_err(|e| e.to_string())?,
+        guild_id: row.get(1).map_err(|e| e.to_string())?,
+        channel_id: row.get(2).map_err(|e| e.to_string())?,
+        content: row.get(3).map_err(|e| e.to_string())?,
+        author: row.get(4).map_err(|e| e.to_string())?,
+        author_id: row.get(5).unwrap_or_default(),
+        timestamp: row.get(6).map_err(|e| e.to_string())?,
+        embeds: serde_json::from_str(&embeds_json).unwrap_or_default(),
+        attachments: serde_json::from_str(&attachments_json).unwrap_or_default(),
+        referenced_message: None,
+        message_snapshots: vec![],
+        kind: "Default".to_string(),
+        reactions: serde_json::from_str(&reactions_json).unwrap_or_default(),
+        components: serde_json::from_str(&components_json).unwrap_or_default(),
+    })
+}
+
+// CSVのフィールドをエスケープ
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// チャンネル履歴をファイルにエクスポート (format: "json" | "csv")
+// 数万件規模のチャンネルでも巨大なStringを作らないよう、1行ずつファイルへ書き出す
+#[tauri::command]
+pub fn export_channel(
+    channel_id: String,
+    format: String,
+    path: String,
+    state: State<'_, DatabaseState>,
+) -> Result<u32, String> {
+    use std::io::Write;
+
+    let is_json = match format.to_lowercase().as_str() {
+        "json" => true,
+        "csv" => false,
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT id, guild_id, channel_id, content, author, author_id, timestamp, embeds, attachments, reactions, components
+         FROM messages
+         WHERE channel_id = ?1
+         ORDER BY timestamp ASC"
+    ).map_err(|e| e.to_string())?;
+    let mut rows = stmt.query(params![channel_id]).map_err(|e| e.to_string())?;
+
+    let file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+    let mut writer = std::io::BufWriter::new(file);
+    let mut count: u32 = 0;
+
+    if is_json {
+        writer.write_all(b"[\n").map_err(|e| e.to_string())?;
+    } else {
+        writer.write_all(b"id,timestamp,author_id,author,content,attachments\n").map_err(|e| e.to_string())?;
+    }
+
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let msg = row_to_message(row)?;
+
+        if is_json {
+            if count > 0 {
+                writer.write_all(b",\n").map_err(|e| e.to_string())?;
+            }
+            serde_json::to_writer(&mut writer, &msg).map_err(|e| e.to_string())?;
+        } else {
+            let attachment_urls = msg.attachments.iter()
+                .map(|a| a.url.clone())
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                csv_field(&msg.id),
+                csv_field(&msg.timestamp),
+                csv_field(&msg.author_id),
+                csv_field(&msg.author),
+                csv_field(&msg.content),
+                csv_field(&attachment_urls),
+            ).map_err(|e| e.to_string())?;
+        }
+        count += 1;
+    }
+
+    if is_json {
+        writer.write_all(b"\n]\n").map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+
+    Ok(count)
+}
+
+// チャンネルごとのキャッシュ統計
+#[derive(serde::Serialize)]
+pub struct ChannelCacheStats {
+    pub channel_id: String,
+    pub message_count: u32,
+    pub oldest_timestamp: Option<String>,
+    pub newest_timestamp: Option<String>,
+}
+
+// キャッシュ済みメッセージの統計を取得 (guild_id 未指定なら全体)
+#[tauri::command]
+pub fn get_cache_stats(
+    guild_id: Option<String>,
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<ChannelCacheStats>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let map_row = |row: &rusqlite::Row| -> rusqlite::Result<ChannelCacheStats> {
+        Ok(ChannelCacheStats {
+            channel_id: row.get(0)?,
+            message_count: row.get(1)?,
+            oldest_timestamp: row.get(2)?,
+            newest_timestamp: row.get(3)?,
+        })
+    };
+
+    let stats = if let Some(gid) = &guild_id {
+        let mut stmt = conn.prepare(
+            "SELECT channel_id, COUNT(*), MIN(timestamp), MAX(timestamp)
+             FROM messages WHERE guild_id = ?1 GROUP BY channel_id"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map(params![gid], map_row).map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    } else {
+        let mut stmt = conn.prepare(
+            "SELECT channel_id, COUNT(*), MIN(timestamp), MAX(timestamp)
+             FROM messages GROUP BY channel_id"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], map_row).map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    };
+
+    Ok(stats)
+}
+
+// 指定ギルドのキャッシュ済みメッセージを全削除 (サーバー脱退時など)
+pub fn delete_guild_messages(conn: &Connection, guild_id: &str) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM messages_fts WHERE id IN (SELECT id FROM messages WHERE guild_id = ?1)",
+        params![guild_id],
+    ).map_err(|e| e.to_string())?;
+    let removed = conn.execute("DELETE FROM messages WHERE guild_id = ?1", params![guild_id])
+        .map_err(|e| e.to_string())?;
+    message_cache::clear();
+    Ok(removed)
+}
+
+// 古いメッセージを削除してDBサイズを回収する
+// チャンネルごとに最新 keep_minimum_per_channel 件は期限切れでも残す
+#[tauri::command]
+pub fn prune_messages(
+    older_than_days: u32,
+    keep_minimum_per_channel: u32,
+    state: State<'_, DatabaseState>,
+) -> Result<u32, String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let removed = {
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        // 削除対象IDを一時テーブルに抽出 (timestampはISO8601文字列なので文字列比較で判定)
+        tx.execute_batch("DROP TABLE IF EXISTS temp.prune_ids; CREATE TEMP TABLE prune_ids (id TEXT PRIMARY KEY);")
+            .map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO temp.prune_ids (id)
+             SELECT id FROM (
+                 SELECT id, timestamp,
+                        ROW_NUMBER() OVER (PARTITION BY channel_id ORDER BY timestamp DESC) AS rn
+                 FROM messages
+             )
+             WHERE rn > ?1 AND timestamp < strftime('%Y-%m-%dT%H:%M:%S', 'now', ?2)",
+            params![keep_minimum_per_channel, format!("-{} days", older_than_days)],
+        ).map_err(|e| e.to_string())?;
+
+        tx.execute("DELETE FROM messages_fts WHERE id IN (SELECT id FROM temp.prune_ids)", [])
+            .map_err(|e| e.to_string())?;
+        let removed = tx.execute("DELETE FROM messages WHERE id IN (SELECT id FROM temp.prune_ids)", [])
+            .map_err(|e| e.to_string())?;
+        tx.execute_batch("DROP TABLE temp.prune_ids;").map_err(|e| e.to_string())?;
+
+        tx.commit().map_err(|e| e.to_string())?;
+        removed
+    };
+    message_cache::clear();
+
+    // VACUUMはトランザクション外で実行する必要がある
+    if removed > 0 {
+        conn.execute_batch("VACUUM;").map_err(|e| e.to_string())?;
+    }
+
+    Ok(removed as u32)
+}
@@ -0,0 +1,78 @@
+// read_states - チャンネルごとの既読位置 (未読バッジ用)
+// Discordへの既読通知 (ack) とは別にローカルにも保持し、起動直後やオフラインでも未読表示できるようにする
+
+use rusqlite::{Connection, params};
+use tauri::State;
+
+use super::DatabaseState;
+
+/// チャンネルの既読状態
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ReadState {
+    pub channel_id: String,
+    pub last_read_id: Option<String>,
+    pub mention_count: u32,
+}
+
+/// read_states テーブルを作成 (migrations から呼ぶ)
+pub fn init_read_states_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS read_states (
+            channel_id TEXT PRIMARY KEY,
+            last_read_id TEXT,
+            mention_count INTEGER NOT NULL DEFAULT 0
+        );
+        "
+    ).map_err(|e| e.to_string())
+}
+
+/// 既読状態を保存 (既存の行は上書き)
+pub fn upsert_read_state(conn: &Connection, read_state: &ReadState) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO read_states (channel_id, last_read_id, mention_count) VALUES (?1, ?2, ?3)",
+        params![read_state.channel_id, read_state.last_read_id, read_state.mention_count],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 複数の既読状態をまとめて保存 (READY 受信時など)
+pub fn save_read_states(conn: &mut Connection, read_states: &[ReadState]) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for read_state in read_states {
+        upsert_read_state(&tx, read_state)?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// 既読状態を記録
+#[tauri::command]
+pub fn set_read_state(
+    channel_id: String,
+    last_read_id: Option<String>,
+    mention_count: Option<u32>,
+    state: State<'_, DatabaseState>,
+) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    upsert_read_state(&conn, &ReadState {
+        channel_id,
+        last_read_id,
+        mention_count: mention_count.unwrap_or(0),
+    })
+}
+
+// 保存済みの既読状態を全件取得
+#[tauri::command]
+pub fn get_read_states(state: State<'_, DatabaseState>) -> Result<Vec<ReadState>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT channel_id, last_read_id, mention_count FROM read_states")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ReadState {
+            channel_id: row.get(0)?,
+            last_read_id: row.get(1)?,
+            mention_count: row.get(2)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
@@ -3,17 +3,21 @@ import { useSessionStore } from './stores/sessionStore';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { VoiceLayout } from './components/VoiceLayout';
-import { ChannelChat } from './components/ChannelChat';
+import { ChannelChat, MessageComponent } from './components/ChannelChat';
 import { DMList, DMChannel } from './components/DMList';
 import { MemberSidebar, SimpleRole, MemberWithPresence } from './components/MemberSidebar';
 import { useWebRTC } from './hooks/useWebRTC';
 import { useWindowPosition } from './hooks/useWindowPosition';
 import { registerCoreCommands } from './services/commands/definitions/core';
+import { formatError } from './utils/textUtils';
 
 interface Guild {
     id: string;
     name: string;
     icon?: string;
+    icon_url?: string;
+    approximate_member_count?: number;
+    approximate_presence_count?: number;
 }
 
 interface Channel {
@@ -23,6 +27,9 @@ interface Channel {
     parent_id?: string;
     position: number;
     last_message_id?: string;
+    topic?: string | null;
+    nsfw?: boolean;
+    rate_limit_per_user?: number; // 低速モードの秒数
 }
 
 interface Embed {
@@ -33,6 +40,11 @@ interface Embed {
     footer?: { text: string; icon_url?: string };
     image?: { url: string };
     thumbnail?: { url: string };
+    author?: { name: string; url?: string; icon_url?: string };
+    fields?: { name: string; value: string; inline: boolean }[];
+    timestamp?: string;
+    video?: { url?: string; width?: number; height?: number };
+    provider?: { name?: string; url?: string };
 }
 
 interface Attachment {
@@ -57,6 +69,7 @@ interface Message {
     referenced_message?: Message;
     message_snapshots?: MessageSnapshot[];
     kind: string;
+    components?: MessageComponent[];
 }
 
 export interface MessageSnapshot {
@@ -83,8 +96,41 @@ interface SimpleMessage {
     referenced_message?: SimpleMessage | null;
     message_snapshots?: MessageSnapshot[];
     kind: string;
+    components?: MessageComponent[];
+    self_echo?: boolean; // 自分がsend_messageで送信したメッセージのGateway経由のエコー
 }
 
+// Rust側のSimpleMessageをUI用のMessageに変換
+const toMessage = (msg: SimpleMessage): Message => {
+    return {
+        id: msg.id,
+        guild_id: msg.guild_id || "",
+        channel_id: msg.channel_id!,
+        content: msg.content,
+        author: msg.author,
+        author_id: msg.author_id,
+        timestamp: msg.timestamp,
+        embeds: msg.embeds || [],
+        attachments: msg.attachments || [],
+        referenced_message: msg.referenced_message ? {
+            id: msg.referenced_message.id,
+            guild_id: msg.referenced_message.guild_id || "",
+            channel_id: msg.referenced_message.channel_id,
+            content: msg.referenced_message.content,
+            author: msg.referenced_message.author,
+            author_id: msg.referenced_message.author_id,
+            timestamp: msg.referenced_message.timestamp,
+            embeds: msg.referenced_message.embeds || [],
+            attachments: msg.referenced_message.attachments || [],
+            referenced_message: undefined, // Avoid infinite recursion
+            kind: msg.referenced_message.kind || 'Default'
+        } : undefined,
+        message_snapshots: msg.message_snapshots || [],
+        kind: msg.kind || 'Default',
+        components: msg.components || []
+    };
+};
+
 type UserStatus = 'online' | 'idle' | 'dnd' | 'invisible';
 
 const STATUS_CONFIG: Record<UserStatus, { label: string, color: string, indicatorColor: string }> = {
@@ -113,34 +159,12 @@ function App() {
             const msg = event.payload;
             console.log("[App] Realtime Message:", msg);
 
+            // 自分の送信分はsend_messageのレスポンスで表示済み
+            if (msg.self_echo) return;
+
             // Only add if it belongs to the current channel
             if (selectedChannelRef.current === msg.channel_id) {
-                const newMsg: Message = {
-                    id: msg.id,
-                    guild_id: msg.guild_id || "",
-                    channel_id: msg.channel_id!,
-                    content: msg.content,
-                    author: msg.author,
-                    author_id: msg.author_id,
-                    timestamp: msg.timestamp,
-                    embeds: msg.embeds || [],
-                    attachments: msg.attachments || [],
-                    referenced_message: msg.referenced_message ? {
-                        id: msg.referenced_message.id,
-                        guild_id: msg.referenced_message.guild_id || "",
-                        channel_id: msg.referenced_message.channel_id,
-                        content: msg.referenced_message.content,
-                        author: msg.referenced_message.author,
-                        author_id: msg.referenced_message.author_id,
-                        timestamp: msg.referenced_message.timestamp,
-                        embeds: msg.referenced_message.embeds || [],
-                        attachments: msg.referenced_message.attachments || [],
-                        referenced_message: undefined, // Avoid infinite recursion
-                        kind: msg.referenced_message.kind || 'Default'
-                    } : undefined,
-                    message_snapshots: msg.message_snapshots || [],
-                    kind: msg.kind || 'Default'
-                };
+                const newMsg = toMessage(msg);
 
                 setMessages(prev => {
                     if (prev.some(m => m.id === newMsg.id)) return prev;
@@ -232,21 +256,20 @@ function App() {
         selectedChannelRef.current = selectedChannel;
     }, [selectedChannel]);
 
-    // Sync Audio State on Mount
+    // Gateway再接続時に取りこぼしたメッセージを補完できるよう、表示中のチャンネルをバックエンドへ通知
     useEffect(() => {
-        invoke<{ isMuted: boolean, isDeafened: boolean }>('get_audio_state')
-            .then(state => {
-                setIsMuted(state.isMuted);
-                setIsDeafened(state.isDeafened);
-            })
-            .catch(e => console.error("Failed to get audio state:", e));
-    }, []);
+        invoke('set_active_channel', { guildId: selectedGuild, channelId: selectedChannel }).catch(console.error);
+    }, [selectedGuild, selectedChannel]);
 
     const [messages, setMessages] = useState<Message[]>([]);
     const [isLoadingChannel, setIsLoadingChannel] = useState(false);
     // const [isSwitchingChannel, setIsSwitchingChannel] = useState(false); // Removed masking logic
     const fetchIdRef = useRef(0); // フェッチバージョン管理用
     const hasMoreRef = useRef(true); // 追加読み込み可能かどうかのフラグ
+    // 低速モード: チャンネルごとの次に送信できる時刻 (ms) と、カウントダウン表示用の現在時刻
+    const [slowmodeUntil, setSlowmodeUntil] = useState<Record<string, number>>({});
+    const [slowmodeNow, setSlowmodeNow] = useState(Date.now());
+    const [typingUsers, setTypingUsers] = useState<Record<string, string[]>>({}); // channel_id -> 入力中のユーザーID
 
     // Collapsed Categories State
     const [collapsedCategories, setCollapsedCategories] = useState<Set<string>>(new Set());
@@ -266,8 +289,9 @@ function App() {
         console.log(`[App] Fetching guild data for ${guildId}`);
         setIsLoadingMembers(true);
         try {
-            // ロールはREST APIで取得可能
-            const fetchedRoles = await invoke<SimpleRole[]>('get_roles', { guildId });
+            // ロールはGUILD_CREATEで受信済みならストアから、無ければREST APIで取得
+            const cachedRoles = await invoke<SimpleRole[] | null>('get_roles_from_store', { guildId });
+            const fetchedRoles = cachedRoles ?? await invoke<SimpleRole[]>('get_roles', { guildId });
             console.log(`[App] Fetched ${fetchedRoles.length} roles.`);
             setRoles(fetchedRoles);
 
@@ -275,9 +299,21 @@ function App() {
             const storedMembers = await invoke<MemberWithPresence[]>('get_guild_members_from_store', { guildId });
             console.log(`[App] Got ${storedMembers.length} members from store.`);
             setMembers(storedMembers);
+
+            // ストアが空 (Gateway未受信) ならREST の名簿にプレゼンスを重ねたものを使う
+            if (storedMembers.length === 0) {
+                try {
+                    const mergedMembers = await invoke<MemberWithPresence[]>('get_members_merged', { guildId });
+                    console.log(`[App] Got ${mergedMembers.length} members from REST.`);
+                    setMembers(mergedMembers);
+                } catch (e) {
+                    // メンバー一覧の取得権限が無い場合は Gateway からの更新を待つ
+                    console.warn("Failed to fetch members via REST:", e);
+                }
+            }
         } catch (e) {
             console.error("Failed to fetch guild data:", e);
-            setStatus(`Guild Data Error: ${e}`);
+            setStatus(`Guild Data Error: ${formatError(e)}`);
         } finally {
             setIsLoadingMembers(false);
         }
@@ -317,13 +353,64 @@ function App() {
             }
         });
 
+        // ロールの追加・変更・削除 (メンバー名の色に反映)
+        const unlistenRoles = listen('roles_update', async (event: any) => {
+            const { guild_id, roles: updatedRoles } = event.payload;
+            if (guild_id === selectedGuild && updatedRoles) {
+                setRoles(updatedRoles);
+                const storedMembers = await invoke<MemberWithPresence[]>('get_guild_members_from_store', { guildId: guild_id });
+                setMembers(storedMembers);
+            }
+        });
+
+        // チャンネル・スレッドの追加・変更・削除 (Gatewayでストアを更新済み)
+        // フォーラムを開いたときに別途取得したスレッドはストアに無いので、削除されたもの以外は残す
+        const unlistenChannels = listen('channels_update', (event: any) => {
+            const { guild_id, channels: updatedChannels, removed_ids } = event.payload as { guild_id: string, channels: Channel[], removed_ids: string[] };
+            if (guild_id !== selectedGuild) return;
+            setChannels(prev => {
+                const updatedIds = new Set(updatedChannels.map(c => c.id));
+                const removed = new Set(removed_ids);
+                const forumIds = new Set(updatedChannels.filter(c => c.kind === 'Forum').map(c => c.id));
+                const forumThreads = prev.filter(c =>
+                    !updatedIds.has(c.id) && !removed.has(c.id) && c.parent_id && forumIds.has(c.parent_id));
+                return [...updatedChannels, ...forumThreads];
+            });
+        });
+
         return () => {
             unlistenMemberList.then(unlisten => unlisten());
             unlistenPresence.then(unlisten => unlisten());
+            unlistenRoles.then(unlisten => unlisten());
+            unlistenChannels.then(unlisten => unlisten());
         };
     }, [selectedGuild]);
 
 
+    // Gateway イベントリスナー: チャンネルごとの入力中ユーザー (期限切れはRust側で処理済み)
+    useEffect(() => {
+        const unlistenTyping = listen<{ channel_id: string, user_ids: string[] }>('typing_update', (event) => {
+            const { channel_id, user_ids } = event.payload;
+            setTypingUsers(prev => ({ ...prev, [channel_id]: user_ids }));
+        });
+
+        return () => {
+            unlistenTyping.then(unlisten => unlisten());
+        };
+    }, []);
+
+    // Gateway イベントリスナー: READY の private_channels からDM一覧を受け取る
+    useEffect(() => {
+        const unlistenDms = listen<DMChannel[]>('dms_ready', (event) => {
+            console.log("[App] Received DMs from READY:", event.payload.length);
+            setDms(event.payload);
+        });
+
+        return () => {
+            unlistenDms.then(unlisten => unlisten());
+        };
+    }, []);
+
     // Close status menu when clicking outside
     useEffect(() => {
         const handleClickOutside = (event: MouseEvent) => {
@@ -353,22 +440,23 @@ function App() {
     const [isMuted, setIsMuted] = useState(false);
     const [isDeafened, setIsDeafened] = useState(false);
 
-    const handleToggleMute = async () => {
-        try {
-            const newState = await invoke<boolean>('toggle_mute');
-            setIsMuted(newState);
-        } catch (e) {
-            console.error("Failed to toggle mute:", e);
+    const handleToggleMute = () => {
+        const newState = !isMuted;
+        // マイクのトラックはWebRTC側で有効/無効を切り替える
+        if (webrtc.isMuted !== newState) {
+            webrtc.toggleMute();
         }
+        setIsMuted(newState);
+        // Discord側のボイス状態にも反映 (OP 4)
+        invoke('set_self_mute_deaf', { selfMute: newState, selfDeaf: isDeafened })
+            .catch(e => console.warn("Failed to sync voice state:", e));
     };
 
-    const handleToggleDeafen = async () => {
-        try {
-            const newState = await invoke<boolean>('toggle_deafen');
-            setIsDeafened(newState);
-        } catch (e) {
-            console.error("Failed to toggle deafen:", e);
-        }
+    const handleToggleDeafen = () => {
+        const newState = !isDeafened;
+        setIsDeafened(newState);
+        invoke('set_self_mute_deaf', { selfMute: isMuted, selfDeaf: newState })
+            .catch(e => console.warn("Failed to sync voice state:", e));
     };
 
     // VAD State (Global)
@@ -408,6 +496,17 @@ function App() {
 
     const [isFetchingHistory, setIsFetchingHistory] = useState(false);
     const channelOpenTimeRef = useRef<number | null>(null);
+    const historyFetchChannelRef = useRef<string | null>(null); // 全履歴取得中のチャンネル
+
+    // チャンネルを離れたら、そのチャンネルの全履歴取得を中断
+    useEffect(() => {
+        const channelId = selectedChannel;
+        return () => {
+            if (channelId && historyFetchChannelRef.current === channelId) {
+                invoke('cancel_history_fetch', { channelId }).catch(console.error);
+            }
+        };
+    }, [selectedChannel]);
 
     // 10分タイマーで全履歴取得開始
     useEffect(() => {
@@ -426,15 +525,18 @@ function App() {
             if (elapsed >= 10 * 60 * 1000 && !isFetchingHistory) { // 10分
                 setIsFetchingHistory(true);
                 setStatus('Fetching history in background...');
+                historyFetchChannelRef.current = selectedChannel;
                 try {
-                    const fetched = await invoke<number>('fetch_all_history', {
+                    const result = await invoke<{ total_fetched: number, completed: boolean, truncated: boolean, cancelled: boolean }>('fetch_all_history', {
                         guildId: selectedGuild,
                         channelId: selectedChannel
                     });
-                    setStatus(`Background fetch complete: ${fetched} messages saved`);
+                    const outcome = result.completed ? 'complete' : result.cancelled ? 'cancelled' : 'stopped early';
+                    setStatus(`Background fetch ${outcome}: ${result.total_fetched} messages saved`);
                 } catch (e) {
-                    setStatus(`Background fetch failed: ${e}`);
+                    setStatus(`Background fetch failed: ${formatError(e)}`);
                 }
+                historyFetchChannelRef.current = null;
                 setIsFetchingHistory(false);
                 clearInterval(checkTimer);
             }
@@ -445,6 +547,28 @@ function App() {
 
     const messagesEndRef = useRef<HTMLDivElement>(null);
 
+    // 自動退席 (AFK): p2d_afk_timeout_minutes が保存されている場合のみ有効 (オプトイン)
+    // 操作があれば最大30秒に1回バックエンドへ通知する
+    useEffect(() => {
+        if (!isLoggedIn) return;
+        const minutes = Number(localStorage.getItem('p2d_afk_timeout_minutes') ?? 0);
+        invoke('set_afk_timeout', { minutes: minutes > 0 ? minutes : null }).catch(console.error);
+
+        let lastPing = 0;
+        const onActivity = () => {
+            const now = Date.now();
+            if (now - lastPing < 30000) return;
+            lastPing = now;
+            invoke('user_active').catch(console.error);
+        };
+        window.addEventListener('mousemove', onActivity);
+        window.addEventListener('keydown', onActivity);
+        return () => {
+            window.removeEventListener('mousemove', onActivity);
+            window.removeEventListener('keydown', onActivity);
+        };
+    }, [isLoggedIn]);
+
     // Load token from localStorage on mount and try auto-login
     useEffect(() => {
         const savedToken = localStorage.getItem('discord_token');
@@ -475,7 +599,7 @@ function App() {
 
             // Initial Data Load
             fetchGuilds();
-            fetchDMs(); // Load DMs as well
+            // DM一覧は Gateway の READY (dms_ready) で届く
 
             // Start Gateway
             invoke('start_gateway', { token: tokenToUse });
@@ -483,7 +607,7 @@ function App() {
             // Listen for real-time messages - REMOVED (Handled in useEffect)
             // await listen<Message>('message_create', (event) => { ... });
         } catch (e) {
-            setStatus(`Error: ${e}`);
+            setStatus(`Error: ${formatError(e)}`);
             localStorage.removeItem('discord_token'); // Remove invalid token
         }
     };
@@ -505,22 +629,24 @@ function App() {
         setStatus('Logged out');
     };
 
-    const fetchDMs = async () => {
-        try {
-            const res = await invoke<DMChannel[]>('get_dms');
-            console.log("[App] Fetched DMs:", res.length);
-            setDms(res);
-        } catch (e) {
-            setStatus(`Error fetching DMs: ${e}`);
-        }
-    };
+    // トークン失効 (REST が 401) 時はRust側でクライアントが破棄されるので、ログアウトして再ログインを促す
+    useEffect(() => {
+        const unlistenAuth = listen('auth_expired', () => {
+            handleLogout();
+            setStatus('Session expired. Please log in again.');
+        });
+
+        return () => {
+            unlistenAuth.then(unlisten => unlisten());
+        };
+    }, []);
 
     const fetchGuilds = async () => {
         try {
             const res = await invoke<Guild[]>('get_guilds');
             setGuilds(res);
         } catch (e) {
-            setStatus(`Error fetching guilds: ${e}`);
+            setStatus(`Error fetching guilds: ${formatError(e)}`);
         }
     };
 
@@ -531,11 +657,13 @@ function App() {
         setSelectedDmId(null);
         setMessages([]);
         try {
-            const res = await invoke<Channel[]>('get_channels', { guildId });
+            // GUILD_CREATEで受信済みならストアから取得 (REST呼び出しを省略)
+            const cached = await invoke<Channel[] | null>('get_channels_from_store', { guildId });
+            const res = cached ?? await invoke<Channel[]>('get_channels', { guildId });
             setChannels(res);
             fetchGuildData(guildId); // Fetch members/roles when guild changes
         } catch (e) {
-            setStatus(`Error fetching channels: ${e}`);
+            setStatus(`Error fetching channels: ${formatError(e)}`);
         }
     };
 
@@ -586,6 +714,9 @@ function App() {
                 setVoiceTransitioning(true);
                 setConnectedVoiceChannel(channelId);
                 setConnectedVoiceChannel(channelId);
+                // Discord側のボイスチャンネルにも参加 (OP 4)
+                invoke('set_voice_channel', { guildId: selectedGuild, channelId })
+                    .catch(e => console.warn("Failed to join Discord voice channel:", e));
                 setMessages([]);
                 hasMoreRef.current = true;
                 setIsLoadingChannel(false);
@@ -597,7 +728,7 @@ function App() {
                     await webrtc.startMicrophone();
                 } catch (e) {
                     console.error("Failed to join voice:", e);
-                    setStatus(`Voice Error: ${e}`);
+                    setStatus(`Voice Error: ${formatError(e)}`);
                 } finally {
                     // Allow next operation after delay
                     setTimeout(() => setVoiceTransitioning(false), 1000);
@@ -647,7 +778,8 @@ function App() {
                             kind: m.referenced_message.kind || 'Default'
                         } : undefined,
                         message_snapshots: m.message_snapshots || [],
-                        kind: m.kind || 'Default'
+                        kind: m.kind || 'Default',
+                        components: m.components || []
                     }));
 
                     if (currentFetchId === fetchIdRef.current) {
@@ -727,7 +859,8 @@ function App() {
                         kind: m.referenced_message.kind || 'Default'
                     } : undefined,
                     message_snapshots: m.message_snapshots || [],
-                    kind: m.kind || 'Default'
+                    kind: m.kind || 'Default',
+                    components: m.components || []
                 }));
 
                 if (fetchedMsgs.length === 0) {
@@ -752,7 +885,7 @@ function App() {
             } catch (e) {
                 console.error("[App] fetch_messages failed:", e);
                 if (currentFetchId === fetchIdRef.current) {
-                    setStatus(`Fetch Error: ${e}`);
+                    setStatus(`Fetch Error: ${formatError(e)}`);
                 }
             } finally {
                 if (currentFetchId === fetchIdRef.current) {
@@ -853,7 +986,7 @@ function App() {
             setSearchResults(localResults);
 
             try {
-                const apiResults = await invoke<Message[]>('search_discord_api', {
+                const { messages: apiResults, total_results } = await invoke<{ messages: Message[], total_results: number }>('search_discord_api', {
                     guildId: selectedGuild,
                     query: searchQuery.trim()
                 });
@@ -867,15 +1000,15 @@ function App() {
                         merged.sort((a, b) => new Date(b.timestamp).getTime() - new Date(a.timestamp).getTime());
                         return merged;
                     });
-                    setStatus(`Found ${localResults.length} local + ${apiResults.length} from API`);
+                    setStatus(`Found ${localResults.length} local + ${apiResults.length} of ${total_results} from API`);
                 } else {
                     setStatus(`Found ${localResults.length} results`);
                 }
-            } catch {
-                setStatus(`Found ${localResults.length} results (API unavailable)`);
+            } catch (e) {
+                setStatus(`Found ${localResults.length} results (API unavailable: ${formatError(e)})`);
             }
         } catch (e) {
-            setStatus(`Search Error: ${e}`);
+            setStatus(`Search Error: ${formatError(e)}`);
         }
         setIsSearching(false);
     };
@@ -883,21 +1016,51 @@ function App() {
     const handleSendMessage = async (content: string, replyToId: string | null = null) => {
         // Allow if viewMode is DM even if selectedGuild is null
         if (!content.trim() || !selectedChannel || (!selectedGuild && viewMode !== 'dm')) return;
+
+        // 低速モード: サーバーから待ち時間 (20016) が返った後、その間は送信しない
+        // 送信成功時にはカウントダウンを始めない (MANAGE_MESSAGES / MANAGE_CHANNELS を持つユーザーは対象外のため)
+        const remaining = Math.ceil(((slowmodeUntil[selectedChannel] ?? 0) - Date.now()) / 1000);
+        if (remaining > 0) {
+            setStatus(`Slowmode: wait ${remaining}s`);
+            return;
+        }
+
         try {
-            await invoke('send_message', {
+            const sent = await invoke<SimpleMessage>('send_message', {
                 guildId: selectedGuild || "",
                 channelId: selectedChannel,
                 content: content.trim(),
                 replyTo: replyToId
             });
-            // Optimistic update or wait for event is fine. 
-            // We listen to 'message_create' so it should appear automatically.
+            // RESTレスポンスを即座に表示する。Gatewayのエコーはself_echo付きで届くので二重表示されない
+            // (エコーが先に届いた場合に備えてIDでも重複チェック)
+            if (selectedChannelRef.current === sent.channel_id) {
+                const newMsg = toMessage(sent);
+                setMessages(prev => prev.some(m => m.id === newMsg.id) ? prev : [...prev, newMsg]);
+            }
         } catch (e) {
             console.error("Failed to send message:", e);
-            setStatus(`Send Error: ${e}`);
+            // 低速モード (Slowmode) は残り秒数が返るので、その間は送信欄を無効にしてカウントダウンする
+            const err = e as { kind?: string, retry_after?: number };
+            if (err?.kind === 'Slowmode' && err.retry_after) {
+                const retryAfter = err.retry_after;
+                setSlowmodeUntil(prev => ({ ...prev, [selectedChannel]: Date.now() + retryAfter * 1000 }));
+                setSlowmodeNow(Date.now());
+            }
+            setStatus(`Send Error: ${formatError(e)}`);
         }
     };
 
+    // 低速モードの待ち時間がある間だけ1秒ごとにカウントダウンを更新
+    const slowmodeRemaining = selectedChannel
+        ? Math.max(0, Math.ceil(((slowmodeUntil[selectedChannel] ?? 0) - slowmodeNow) / 1000))
+        : 0;
+    useEffect(() => {
+        if (slowmodeRemaining <= 0) return;
+        const timer = setTimeout(() => setSlowmodeNow(Date.now()), 1000);
+        return () => clearTimeout(timer);
+    }, [slowmodeRemaining, slowmodeNow]);
+
     const handleMessageDeleted = (messageId: string) => {
         setMessages(prev => prev.filter(m => m.id !== messageId));
     };
@@ -983,6 +1146,13 @@ function App() {
                 selectedChannel={selectedChannel}
                 selectedGuild={selectedGuild}
                 channelName={currentCh?.name}
+                slowmodeRemaining={slowmodeRemaining}
+                typingNames={(typingUsers[selectedChannel] ?? [])
+                    .filter(id => id !== myId)
+                    .map(id => {
+                        const member = members.find(m => m.user.id === id);
+                        return member?.nick || member?.user.username || 'Someone';
+                    })}
                 channels={channels as any}
                 messages={messages}
                 searchResults={searchResults}
@@ -1058,10 +1228,13 @@ function App() {
                         <div
                             key={g.id}
                             onClick={() => fetchChannels(g.id)}
+                            title={g.approximate_member_count != null
+                                ? `${g.name} — ${g.approximate_presence_count ?? 0} online / ${g.approximate_member_count} members`
+                                : g.name}
                             className={`p-3 cursor-pointer hover:bg-gray-900 truncate flex items-center gap-2 ${selectedGuild === g.id ? 'bg-gray-800 text-white' : 'text-gray-400'}`}
                         >
-                            {g.icon ? (
-                                <img src={`https://cdn.discordapp.com/icons/${g.id}/${g.icon}.png`} className="w-8 h-8 rounded-full" alt="" />
+                            {g.icon_url ? (
+                                <img src={g.icon_url} className="w-8 h-8 rounded-full" alt="" />
                             ) : (
                                 <div className="w-8 h-8 rounded-full bg-gray-700 flex items-center justify-center text-sm font-bold text-white">
                                     {g.name.substring(0, 1)}
@@ -1242,6 +1415,8 @@ function App() {
                                     } catch (e) {
                                         console.error("Failed to leave room:", e);
                                     }
+                                    invoke('set_voice_channel', { guildId: null, channelId: null })
+                                        .catch(e => console.warn("Failed to leave Discord voice channel:", e));
                                     setConnectedVoiceChannel(null);
                                     clearPeers();
                                 }}
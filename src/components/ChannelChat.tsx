@@ -4,6 +4,7 @@ import { MentionPicker, useMentionPicker } from './MentionPicker';
 import { SlashCommandPicker, useSlashCommandPicker, BotCommand } from './SlashCommandPicker';
 import { useState, useEffect, useMemo, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 export interface Channel {
     id: string;
@@ -12,6 +13,9 @@ export interface Channel {
     parent_id?: string;
     position: number;
     last_message_id?: string;
+    topic?: string | null;
+    nsfw?: boolean;
+    rate_limit_per_user?: number; // 低速モードの秒数
 }
 
 export interface Attachment {
@@ -29,8 +33,27 @@ export interface Embed {
     description?: string;
     url?: string;
     color?: number;
+    footer?: { text: string; icon_url?: string };
     image?: { url: string };
     thumbnail?: { url: string };
+    author?: { name: string; url?: string; icon_url?: string };
+    fields?: { name: string; value: string; inline: boolean }[];
+    timestamp?: string;
+    video?: { url?: string; width?: number; height?: number };
+    provider?: { name?: string; url?: string };
+}
+
+export interface MessageComponent {
+    kind: number; // 1: ActionRow, 2: Button, 3/5/6/7/8: セレクトメニュー
+    style?: number;
+    label?: string;
+    custom_id?: string;
+    url?: string;
+    emoji?: { id?: string; name?: string; animated?: boolean };
+    placeholder?: string;
+    disabled: boolean;
+    options: { label: string; value: string; description?: string; default: boolean }[];
+    components: MessageComponent[];
 }
 
 export interface Message {
@@ -46,6 +69,7 @@ export interface Message {
     referenced_message?: Message;
     message_snapshots?: MessageSnapshot[];
     kind: string; // "Default", "UserJoin", "ChannelPin", etc.
+    components?: MessageComponent[];
 }
 
 export interface MessageSnapshot {
@@ -63,6 +87,8 @@ interface ChannelChatProps {
     selectedChannel: string | null;
     selectedGuild: string | null; // 追加: BOTコマンド実行に必要
     channelName?: string;
+    slowmodeRemaining?: number; // 低速モードで送信できるまでの残り秒数 (0 なら送信可)
+    typingNames?: string[]; // 入力中のユーザー名 (自分以外)
     channels: Channel[]; // Needed for referencing channel names in search results
     messages: Message[];
     searchResults: Message[] | null;
@@ -86,11 +112,31 @@ interface ChannelChatProps {
     messagesEndRef: React.RefObject<HTMLDivElement>;
 }
 
+// INTERACTION_MODAL_CREATE で受け取るモーダル (ActionRow > TextInput)
+interface BotModal {
+    id: string;
+    channel_id: string;
+    application_id: string;
+    custom_id: string;
+    title: string;
+    components: Array<{
+        components: Array<{
+            custom_id: string;
+            label: string;
+            style: number; // 1 = 1行, 2 = 複数行
+            placeholder?: string;
+            value?: string;
+        }>;
+    }>;
+}
+
 export function ChannelChat({
     status,
     selectedChannel,
     selectedGuild,
     channelName,
+    slowmodeRemaining = 0,
+    typingNames = [],
     channels,
     messages,
     searchResults,
@@ -170,6 +216,9 @@ export function ChannelChat({
         fetchBotCommands();
     }, [selectedGuild]);
 
+    // 表示中のチャンネル (ヘッダーのトピック・低速モード表示用)
+    const currentChannel = channels.find(c => c.id === selectedChannel);
+
     // メンションコンテキスト（チャンネル名解決用）
     const mentionContext: MentionContext = useMemo(() => ({
         channels: new Map(channels.map(c => [c.id, { id: c.id, name: c.name }]))
@@ -211,6 +260,120 @@ export function ChannelChat({
         }
     };
 
+    // BOTメッセージのボタン押下・セレクトメニュー選択 (application_id は送信元BOTのID)
+    const handleComponentInteraction = async (msg: Message, component: MessageComponent, values: string[] = []) => {
+        if (!component.custom_id) return;
+        try {
+            await invoke('send_component_interaction', {
+                channelId: msg.channel_id,
+                guildId: msg.guild_id || null,
+                applicationId: msg.author_id,
+                messageId: msg.id,
+                data: {
+                    component_type: component.kind,
+                    custom_id: component.custom_id,
+                    values,
+                },
+            });
+        } catch (e) {
+            console.error('[ChannelChat] Failed to send component interaction:', e);
+        }
+    };
+
+    // BOTが表示したモーダル (INTERACTION_MODAL_CREATE) と入力中の値
+    const [botModal, setBotModal] = useState<BotModal | null>(null);
+    const [modalValues, setModalValues] = useState<Record<string, string>>({});
+
+    useEffect(() => {
+        const unlisten = listen<BotModal>('interaction_modal_create', (event) => {
+            if (event.payload.channel_id !== selectedChannel) return;
+            const initial: Record<string, string> = {};
+            event.payload.components.forEach(row => row.components.forEach(input => {
+                initial[input.custom_id] = input.value ?? '';
+            }));
+            setModalValues(initial);
+            setBotModal(event.payload);
+        });
+        return () => { unlisten.then(f => f()); };
+    }, [selectedChannel]);
+
+    const handleModalSubmit = async () => {
+        if (!botModal) return;
+        try {
+            await invoke('send_modal_submit', {
+                channelId: botModal.channel_id,
+                guildId: selectedGuild || null,
+                applicationId: botModal.application_id,
+                data: {
+                    id: botModal.id,
+                    custom_id: botModal.custom_id,
+                    components: botModal.components.map(row => ({
+                        type: 1,
+                        components: row.components.map(input => ({
+                            type: 4,
+                            custom_id: input.custom_id,
+                            value: modalValues[input.custom_id] ?? '',
+                        })),
+                    })),
+                },
+            });
+            setBotModal(null);
+        } catch (e) {
+            console.error('[ChannelChat] Failed to submit modal:', e);
+        }
+    };
+
+    const BUTTON_STYLES: Record<number, string> = {
+        1: 'bg-indigo-600 hover:bg-indigo-500 text-white',
+        2: 'bg-gray-600 hover:bg-gray-500 text-white',
+        3: 'bg-green-600 hover:bg-green-500 text-white',
+        4: 'bg-red-600 hover:bg-red-500 text-white',
+        5: 'bg-gray-600 hover:bg-gray-500 text-white',
+    };
+
+    const renderComponent = (msg: Message, component: MessageComponent, key: number) => {
+        const emoji = component.emoji?.id
+            ? <img src={`https://cdn.discordapp.com/emojis/${component.emoji.id}.${component.emoji.animated ? 'gif' : 'png'}`} alt={component.emoji.name} className="w-4 h-4" />
+            : component.emoji?.name;
+
+        if (component.kind === 2) {
+            const className = `px-3 py-1 rounded text-sm flex items-center gap-1 disabled:opacity-50 ${BUTTON_STYLES[component.style ?? 2] ?? BUTTON_STYLES[2]}`;
+            // Link ボタンは外部リンクとして開く
+            if (component.style === 5 && component.url) {
+                return (
+                    <a key={key} href={component.url} target="_blank" rel="noreferrer" className={className}>
+                        {emoji}{component.label} ↗
+                    </a>
+                );
+            }
+            return (
+                <button key={key} disabled={component.disabled} className={className} onClick={() => handleComponentInteraction(msg, component)}>
+                    {emoji}{component.label}
+                </button>
+            );
+        }
+
+        if (component.kind === 3) {
+            return (
+                <select
+                    key={key}
+                    disabled={component.disabled}
+                    defaultValue=""
+                    className="bg-gray-900 text-gray-200 text-sm rounded px-2 py-1 min-w-[200px]"
+                    onChange={(e) => e.target.value && handleComponentInteraction(msg, component, [e.target.value])}
+                >
+                    <option value="" disabled>{component.placeholder || '選択してください'}</option>
+                    {component.options.map(opt => (
+                        <option key={opt.value} value={opt.value}>{opt.label}</option>
+                    ))}
+                </select>
+            );
+        }
+
+        // ユーザー/ロール/チャンネル選択などは未対応
+        return null;
+    };
+
     const handleCopyLink = (msg: Message) => {
         const link = `https://discord.com/channels/${msg.guild_id}/${msg.channel_id}/${msg.id}`;
         navigator.clipboard.writeText(link);
@@ -220,9 +383,22 @@ export function ChannelChat({
         <div className="flex-1 flex flex-col bg-black relative h-full">
             <div className="p-4 border-b border-gray-800 flex flex-col gap-2 bg-black/50 backdrop-blur z-10">
                 <div className="flex justify-between items-center">
-                    <span className="font-bold text-lg text-terminal-green">
-                        {selectedChannel ? `#${channelName || 'unknown'}` : 'Chat'}
-                    </span>
+                    <div className="flex items-center gap-2 min-w-0">
+                        <span className="font-bold text-lg text-terminal-green">
+                            {selectedChannel ? `#${channelName || 'unknown'}` : 'Chat'}
+                        </span>
+                        {currentChannel?.nsfw && (
+                            <span className="text-xs px-1 rounded bg-red-900 text-red-300">NSFW</span>
+                        )}
+                        {!!currentChannel?.rate_limit_per_user && (
+                            <span className="text-xs text-gray-400" title="Slowmode">⏱ {currentChannel.rate_limit_per_user}s</span>
+                        )}
+                        {currentChannel?.topic && (
+                            <span className="text-sm text-gray-400 truncate" title={currentChannel.topic}>
+                                | {currentChannel.topic}
+                            </span>
+                        )}
+                    </div>
                     <span className="text-sm text-gray-500">{status}</span>
                 </div>
                 {/* Search Bar */}
@@ -360,9 +536,41 @@ export function ChannelChat({
                                         <div key={idx} className="mt-2 border-l-4 bg-gray-900 rounded p-3" style={{ borderLeftColor: embed.color ? `#${embed.color.toString(16).padStart(6, '0')}` : '#202225' }}>
                                             <div className="flex gap-4">
                                                 <div className="flex-1 min-w-0">
+                                                    {embed.provider?.name && <div className="text-xs text-gray-400 mb-1">{embed.provider.name}</div>}
+                                                    {embed.author && (
+                                                        <div className="flex items-center gap-2 mb-1 text-sm text-white">
+                                                            {embed.author.icon_url && <img src={embed.author.icon_url} alt="" className="w-6 h-6 rounded-full" />}
+                                                            <span className="font-semibold">{embed.author.name}</span>
+                                                        </div>
+                                                    )}
                                                     {embed.title && <div className="font-bold text-white mb-1">{embed.title}</div>}
                                                     {embed.description && <div className="text-gray-300 text-sm whitespace-pre-wrap break-words">{embed.description}</div>}
+                                                    {embed.fields && embed.fields.length > 0 && (
+                                                        <div className="mt-2 grid grid-cols-3 gap-2">
+                                                            {embed.fields.map((field, fIdx) => (
+                                                                <div key={fIdx} className={field.inline ? 'col-span-1' : 'col-span-3'}>
+                                                                    <div className="text-xs font-bold text-white">{field.name}</div>
+                                                                    <div className="text-sm text-gray-300 whitespace-pre-wrap break-words">{field.value}</div>
+                                                                </div>
+                                                            ))}
+                                                        </div>
+                                                    )}
                                                     {embed.image && <img src={embed.image.url} alt="Embed" className="mt-2 max-w-full rounded" style={{ maxHeight: '300px' }} />}
+                                                    {embed.video?.url && /\.(mp4|webm|mov)(\?|$)/i.test(embed.video.url) ? (
+                                                        <video src={embed.video.url} controls className="mt-2 max-w-full rounded" style={{ maxHeight: '300px' }} />
+                                                    ) : embed.video && embed.url && (
+                                                        // YouTube等の埋め込みプレイヤーは外部で開く
+                                                        <a href={embed.url} target="_blank" rel="noreferrer" className="mt-2 inline-block text-sm text-blue-400 hover:underline">
+                                                            ▶ {embed.provider?.name ? `${embed.provider.name}で再生` : '動画を再生'}
+                                                        </a>
+                                                    )}
+                                                    {(embed.footer || embed.timestamp) && (
+                                                        <div className="mt-2 text-xs text-gray-400">
+                                                            {embed.footer?.text}
+                                                            {embed.footer && embed.timestamp && ' • '}
+                                                            {embed.timestamp && new Date(embed.timestamp).toLocaleString()}
+                                                        </div>
+                                                    )}
                                                 </div>
                                                 {embed.thumbnail && (
                                                     <div className="flex-shrink-0">
@@ -373,6 +581,15 @@ export function ChannelChat({
                                         </div>
                                     ))}
 
+                                    {/* Components (Buttons / Select Menus) Rendering */}
+                                    {m.components && m.components.map((row, rowIdx) => (
+                                        <div key={rowIdx} className="mt-2 flex flex-wrap gap-2">
+                                            {row.kind === 1
+                                                ? row.components.map((c, cIdx) => renderComponent(m, c, cIdx))
+                                                : renderComponent(m, row, 0)}
+                                        </div>
+                                    ))}
+
                                     {/* Attachments Rendering */}
                                     {m.attachments && m.attachments.map((att) => (
                                         <div key={att.id} className="mt-2">
@@ -408,6 +625,39 @@ export function ChannelChat({
                     <div ref={messagesEndRef} />
                 </div>
 
+                {/* BOTのモーダル */}
+                {botModal && (
+                    <div className="fixed inset-0 z-50 bg-black/60 flex items-center justify-center">
+                        <div className="bg-[#111] border border-gray-800 rounded shadow-xl w-96 p-4 text-sm">
+                            <div className="text-white font-semibold mb-3">{botModal.title}</div>
+                            {botModal.components.flatMap(row => row.components).map(input => (
+                                <label key={input.custom_id} className="block mb-3">
+                                    <span className="block text-gray-400 mb-1">{input.label}</span>
+                                    {input.style === 2 ? (
+                                        <textarea
+                                            className="w-full bg-gray-900 border border-gray-700 rounded px-2 py-1 text-white"
+                                            placeholder={input.placeholder}
+                                            value={modalValues[input.custom_id] ?? ''}
+                                            onChange={(e) => setModalValues(prev => ({ ...prev, [input.custom_id]: e.target.value }))}
+                                        />
+                                    ) : (
+                                        <input
+                                            className="w-full bg-gray-900 border border-gray-700 rounded px-2 py-1 text-white"
+                                            placeholder={input.placeholder}
+                                            value={modalValues[input.custom_id] ?? ''}
+                                            onChange={(e) => setModalValues(prev => ({ ...prev, [input.custom_id]: e.target.value }))}
+                                        />
+                                    )}
+                                </label>
+                            ))}
+                            <div className="flex justify-end gap-2">
+                                <button className="px-3 py-1 rounded text-gray-300 hover:text-white" onClick={() => setBotModal(null)}>Cancel</button>
+                                <button className="px-3 py-1 rounded bg-indigo-600 hover:bg-indigo-500 text-white" onClick={handleModalSubmit}>Submit</button>
+                            </div>
+                        </div>
+                    </div>
+                )}
+
                 {/* Render Context Menu */}
                 {contextMenu && (
                     <div
@@ -470,6 +720,13 @@ export function ChannelChat({
 
                 {/* Input Area */}
                 <div className="p-4 bg-gray-900 border-t border-gray-800">
+                    {typingNames.length > 0 && (
+                        <div className="text-xs text-gray-400 mb-1 animate-pulse">
+                            {typingNames.length > 3
+                                ? 'Several people are typing…'
+                                : `${typingNames.join(', ')} ${typingNames.length === 1 ? 'is' : 'are'} typing…`}
+                        </div>
+                    )}
                     {replyingTo && (
                         <div className="flex items-center justify-between bg-gray-800 p-2 rounded-t text-xs text-gray-400 border-b border-gray-700 mb-0">
                             <span className="flex items-center gap-1">
@@ -539,9 +796,13 @@ export function ChannelChat({
                             ref={inputRef}
                             type="text"
                             value={inputValue}
-                            placeholder={selectedChannel ? `Message #${channelName || 'unknown'}` : "Select a channel"}
-                            className={`w-full bg-gray-800 border border-gray-700 p-3 rounded ${replyingTo ? 'rounded-t-none' : ''} text-white focus:outline-none focus:border-terminal-green ${!selectedChannel ? 'cursor-not-allowed opacity-50' : ''}`}
-                            disabled={!selectedChannel}
+                            placeholder={!selectedChannel
+                                ? "Select a channel"
+                                : slowmodeRemaining > 0
+                                    ? `Slowmode: you can send again in ${slowmodeRemaining}s`
+                                    : `Message #${channelName || 'unknown'}`}
+                            className={`w-full bg-gray-800 border border-gray-700 p-3 rounded ${replyingTo ? 'rounded-t-none' : ''} text-white focus:outline-none focus:border-terminal-green ${!selectedChannel || slowmodeRemaining > 0 ? 'cursor-not-allowed opacity-50' : ''}`}
+                            disabled={!selectedChannel || slowmodeRemaining > 0}
                             onChange={(e) => {
                                 const value = e.target.value;
                                 setInputValue(value);
@@ -18,6 +18,7 @@ export interface SimpleMember {
     };
     roles: string[];
     nick?: string | null;
+    guild_avatar?: string | null;
     joined_at: string;
 }
 
@@ -31,6 +32,8 @@ export interface MemberWithPresence {
     };
     roles: string[];
     nick?: string | null;
+    guild_avatar?: string | null; // サーバー専用アバターのURL
+    avatar_url?: string | null; // ユーザーアバターのURL
     joined_at: string;
     status: string;  // "online", "idle", "dnd", "offline"
     activities: Activity[];
@@ -184,9 +187,9 @@ export const MemberSidebar: React.FC<MemberSidebarProps> = ({ members, roles, lo
                                 className="mx-2 px-2 py-1.5 flex items-center gap-2 rounded hover:bg-gray-700/50 cursor-pointer group transition-colors opacity-90 hover:opacity-100"
                             >
                                 <div className="relative flex-shrink-0">
-                                    {member.user.avatar ? (
+                                    {member.guild_avatar || member.avatar_url ? (
                                         <img
-                                            src={`https://cdn.discordapp.com/avatars/${member.user.id}/${member.user.avatar}.png?size=64`}
+                                            src={`${member.guild_avatar || member.avatar_url}?size=64`}
                                             alt={member.user.username}
                                             className="w-8 h-8 rounded-full bg-gray-800 object-cover"
                                         />
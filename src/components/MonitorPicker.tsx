@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 interface CaptureSource {
     id: string;
@@ -25,6 +26,14 @@ export const MonitorPicker = ({ onSelect, onCancel, onNativeCapture }: MonitorPi
 
     useEffect(() => {
         loadSources();
+
+        // モニターの接続/切断時は一覧を取り直す
+        const unlistenPromise = listen('monitors-changed', () => {
+            loadSources();
+        });
+        return () => {
+            unlistenPromise.then(unlisten => unlisten());
+        };
     }, []);
 
     const loadSources = async () => {
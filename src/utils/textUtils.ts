@@ -27,3 +27,12 @@ export const linkify = (text: string): React.ReactNode[] => {
         return part;
     });
 };
+
+// Rust側コマンドのエラー ({ kind, message, status } または文字列) を表示用文字列に変換
+export const formatError = (e: unknown): string => {
+    if (typeof e === 'string') return e;
+    if (e && typeof e === 'object' && 'message' in e) {
+        return String((e as { message: unknown }).message);
+    }
+    return String(e);
+};